@@ -1,42 +1,85 @@
 use crate::{
     controllers::{
-        commands::{ArgCommand, CommandResult, StrEnum},
+        commands::{resolve_command, ArgCommand, CommandHelp, CommandResult, HelpTopic, StrEnum},
+        script::{self, ScriptHost},
         Controller,
     },
-    models::model::Model,
-    settings::{BannerColor, Settings},
-    views::{parsed_command::ParsedCommand, View},
+    exit_status::ExitStatus,
+    models::{model::Model, pick_options::PickOptions, topic_filter::TopicFilter},
+    settings::{BannerColor, List, Settings},
+    views::{
+        completions::{self, Shell},
+        parsed_command::ParsedCommand,
+        View,
+    },
 };
 use anyhow::Result;
+use std::fs;
 
+/// The list is only opened by the first command that uses it, so that commands like
+/// `list` or `help` work while the list can't be read.
 pub struct ArgController {
-    model: Model,
+    list: List,
+    model: Option<Model>,
     view: Box<dyn View>,
+    script_depth: usize,
 }
 
 impl Controller for ArgController {
-    fn run(&mut self, settings: &mut Settings) {
-        let parsed_command = self.view.get_input().expect("Incorrect command format.");
-
-        if let CommandResult::Fail(error_message) = self.pass_command(&parsed_command, settings) {
-            self.view.print_error(&error_message);
+    fn run(&mut self, settings: &mut Settings) -> ExitStatus {
+        while let Some(parsed_command) = self.view.get_input() {
+            let result = self.pass_command(&parsed_command, settings);
+            if let Some(error_message) = result.error_message() {
+                self.view.print_error(error_message);
+                return result.exit_status();
+            }
         }
+        ExitStatus::Success
     }
 
     fn close(&mut self) -> Result<()> {
-        if !self.model.topic_handler.is_modified(true) {
+        let Some(model) = &mut self.model else {
             return Ok(());
+        };
+        let result = Self::write_list(model);
+        if let Err(error) = model.save_history() {
+            self.view
+                .print_error(&format!("Failed to save history: {error}"));
         }
-        self.model
-            .topic_writer
-            .write(self.model.topic_handler.get_topics())?;
-        self.model.topic_writer.close()
+        result
+    }
+}
+
+impl ScriptHost for ArgController {
+    fn script_depth(&mut self) -> &mut usize {
+        &mut self.script_depth
+    }
+
+    fn run_command(&mut self, command: &ParsedCommand, settings: &mut Settings) -> CommandResult {
+        self.pass_command(command, settings)
+    }
+
+    fn report_error(&mut self, message: &str) {
+        self.view.print_error(message);
     }
 }
 
 impl ArgController {
-    pub fn new(model: Model, view: Box<dyn View>) -> Self {
-        Self { model, view }
+    pub fn new(list: List, view: Box<dyn View>) -> Self {
+        Self {
+            list,
+            model: None,
+            view,
+            script_depth: 0,
+        }
+    }
+
+    fn write_list(model: &mut Model) -> Result<()> {
+        if !model.topic_handler.is_modified(true) {
+            return Ok(());
+        }
+        model.topic_writer.write(model.topic_handler.get_list())?;
+        model.topic_writer.close()
     }
 
     fn pass_command(
@@ -44,42 +87,198 @@ impl ArgController {
         parsed_command: &ParsedCommand,
         settings: &mut Settings,
     ) -> CommandResult {
-        match ArgCommand::from_str(parsed_command.command()) {
-            Some(command) => match command {
-                ArgCommand::Add => self.model.topic_handler.add_topics(parsed_command.args()),
-                ArgCommand::Pick => self.pick_entry(parsed_command.args()),
-                ArgCommand::Remove => self
-                    .model
-                    .topic_handler
-                    .remove_topics(parsed_command.args()),
-                ArgCommand::Entries => {
-                    self.view.render(
-                        self.model.topic_handler.get_topics(),
-                        "",
-                        &BannerColor::White,
-                    );
-                    CommandResult::Success
-                }
-                ArgCommand::List => {
-                    self.view.print_lists(settings.lists());
-                    CommandResult::Success
-                }
-                ArgCommand::Switch => settings.set_open_in(&parsed_command.args()[0]),
+        let command = match resolve_command(
+            parsed_command.command(),
+            &ArgCommand::ALL_COMMANDS,
+            settings.aliases(),
+        ) {
+            Ok(command) => command,
+            Err(result) => return result,
+        };
+        let Some(command) = ArgCommand::from_str(command) else {
+            return CommandResult::UnknownCommand(format!(
+                "Unknown command: {}\nAvailable commands: {}",
+                parsed_command.command(),
+                ArgCommand::ALL_COMMANDS.join(", ")
+            ));
+        };
+        match command {
+            ArgCommand::List => {
+                self.view.print_lists(settings.lists());
+                CommandResult::Success
+            }
+            ArgCommand::Switch => match parsed_command.args().as_slice() {
+                [] => CommandResult::Fail("Missing argument: list".to_string()),
+                list_query => settings.set_open_in(&list_query.join(" ")),
+            },
+            ArgCommand::Run => script::run_script(self, parsed_command.args(), settings),
+            ArgCommand::Completions => self.print_completions(parsed_command.args()),
+            ArgCommand::Help => self.show_help(parsed_command.args(), settings),
+            command => self.pass_list_command(command, parsed_command.args(), settings),
+        }
+    }
+
+    fn pass_list_command(
+        &mut self,
+        command: ArgCommand,
+        args: &[String],
+        settings: &mut Settings,
+    ) -> CommandResult {
+        let model = match self.model.take() {
+            Some(model) => model,
+            None => match Model::open(&self.list, settings) {
+                Ok(model) => model,
+                Err(error) => return CommandResult::ReadFailed(error.to_string()),
             },
-            None => CommandResult::Fail(format!("Unknown command: {}", parsed_command.command())),
+        };
+        let model = self.model.insert(model);
+        let view = self.view.as_mut();
+        match command {
+            ArgCommand::Add => Self::add_entries(model, view, args),
+            ArgCommand::Import => Self::import_entries(model, view, args),
+            ArgCommand::Pick => Self::pick_entry(model, view, args),
+            ArgCommand::Remove => model.topic_handler.remove_topics(args),
+            ArgCommand::Edit => model.topic_handler.edit_topic(args),
+            ArgCommand::Move => model.topic_handler.move_topic(args),
+            ArgCommand::Swap => model.topic_handler.swap_topics(args),
+            ArgCommand::Sort => model.topic_handler.sort_topics(args),
+            ArgCommand::Dedupe => model.topic_handler.dedupe_topics(args),
+            ArgCommand::MoveTo => model.send_topic(args, settings, false),
+            ArgCommand::CopyTo => model.send_topic(args, settings, true),
+            ArgCommand::Merge => model.merge_list(args, settings),
+            ArgCommand::Entries => Self::show_entries(model, view, args),
+            ArgCommand::Undo => model.topic_handler.undo(),
+            ArgCommand::Redo => model.topic_handler.redo(),
+            ArgCommand::History => Self::show_history(model, view, args),
+            ArgCommand::Archive => {
+                view.print_archive(&model.topic_handler.get_archive_entries());
+                CommandResult::Success
+            }
+            ArgCommand::Restore => model.topic_handler.restore_topics(args),
+            ArgCommand::List
+            | ArgCommand::Switch
+            | ArgCommand::Run
+            | ArgCommand::Completions
+            | ArgCommand::Help => unreachable!("handled without opening the list"),
+        }
+    }
+
+    fn pick_entry(model: &mut Model, view: &mut dyn View, args: &[String]) -> CommandResult {
+        let options = match PickOptions::parse(args) {
+            Ok(options) => options,
+            Err(message) => return CommandResult::Fail(message),
+        };
+        match model.topic_handler.pick(&options) {
+            CommandResult::Success => (),
+            fail => return fail,
+        }
+        view.display_chosen_topics(&model.topic_handler.get_chosen_topics(), &model.list);
+        if options.remove_chosen() {
+            model.topic_handler.remove_chosen_topics()
+        } else {
+            CommandResult::Success
+        }
+    }
+
+    fn show_entries(model: &mut Model, view: &mut dyn View, args: &[String]) -> CommandResult {
+        let entries = match args {
+            [] => model.topic_handler.get_entries(),
+            [flag, filter_args @ ..] if flag == "--filter" => match TopicFilter::parse(filter_args)
+            {
+                Ok(filter) => model.topic_handler.find_entries(&filter),
+                Err(message) => return CommandResult::Fail(message),
+            },
+            [arg, ..] => return CommandResult::Fail(format!("Incorrect argument: {arg}")),
+        };
+        view.render(&entries, &model.list, "", &BannerColor::White);
+        CommandResult::Success
+    }
+
+    fn add_entries(model: &mut Model, view: &mut dyn View, args: &[String]) -> CommandResult {
+        let mut add_args: Vec<String> = args.iter().filter(|arg| *arg != "-").cloned().collect();
+        match args.len() - add_args.len() {
+            0 => (),
+            1 => {
+                add_args.push("--".to_string());
+                add_args.extend(Self::topic_lines(view.read_lines()));
+            }
+            _ => return CommandResult::Fail("Stdin can only be read once".to_string()),
         }
+        model.topic_handler.add_topics(&add_args)
     }
 
-    fn pick_entry(&mut self, args: &[String]) -> CommandResult {
-        self.model.topic_handler.pick_random();
-        if let Some(entry) = self.model.topic_handler.get_chosen_topic() {
-            self.view.display_chosen_topic(entry);
+    fn import_entries(model: &mut Model, view: &mut dyn View, args: &[String]) -> CommandResult {
+        let [source, add_args @ ..] = args else {
+            return CommandResult::Fail("Missing argument: file or -".to_string());
+        };
+        let lines = if source == "-" {
+            view.read_lines()
+        } else {
+            match fs::read_to_string(source) {
+                Ok(content) => content.lines().map(str::to_string).collect(),
+                Err(error) => {
+                    return CommandResult::ReadFailed(format!("Failed to read {source}: {error}"))
+                }
+            }
+        };
+        let mut args = add_args.to_vec();
+        args.push("--".to_string());
+        args.extend(Self::topic_lines(lines));
+        model.topic_handler.add_topics(&args)
+    }
+
+    fn topic_lines(lines: Vec<String>) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn show_help(&mut self, args: &[String], settings: &Settings) -> CommandResult {
+        match CommandHelp::topic(args, &ArgCommand::ALL_COMMANDS, settings.aliases()) {
+            Ok(HelpTopic::Commands(commands)) => self.view.print_command_list(&commands),
+            Ok(HelpTopic::Command(help)) => self.view.print_command_help(help),
+            Err(result) => return result,
         }
-        match args.len() {
-            0 => CommandResult::Success,
-            1 if args[0] == "-y" => self.model.topic_handler.remove_chosen_topic(),
-            1 => CommandResult::Fail(format!("Incorrect argument: {}", args[0])),
-            _ => CommandResult::Fail("Incorrect number of arguments".to_string()),
+        CommandResult::Success
+    }
+
+    fn print_completions(&mut self, args: &[String]) -> CommandResult {
+        let (shell, name) = match args {
+            [shell] => (shell, Self::binary_name()),
+            [shell, name] => (shell, name.clone()),
+            _ => return CommandResult::Fail("Incorrect number of arguments".to_string()),
+        };
+        let Some(shell) = Shell::from_str(shell) else {
+            return CommandResult::Fail(format!(
+                "Unknown shell: {shell}, expected one of {}",
+                Shell::ALL_SHELLS.join(", ")
+            ));
+        };
+        self.view
+            .print_completion_script(&completions::completion_script(shell, &name));
+        CommandResult::Success
+    }
+
+    fn binary_name() -> String {
+        std::env::args()
+            .next()
+            .as_deref()
+            .map(std::path::Path::new)
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| env!("CARGO_PKG_NAME").to_string())
+    }
+
+    fn show_history(model: &mut Model, view: &mut dyn View, args: &[String]) -> CommandResult {
+        match model.topic_handler.recent_picks(args) {
+            Ok(records) => {
+                view.print_pick_history(records);
+                CommandResult::Success
+            }
+            Err(result) => result,
         }
     }
 }
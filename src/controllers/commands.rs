@@ -1,45 +1,482 @@
+use crate::exit_status::ExitStatus;
+use std::collections::HashMap;
+
 #[derive(Copy, Clone)]
 pub enum ArgCommand {
     Add,
+    Import,
     Pick,
     Remove,
+    Edit,
+    Move,
+    Swap,
+    Sort,
+    Dedupe,
+    MoveTo,
+    CopyTo,
+    Merge,
     Entries,
     List,
     Switch,
+    Undo,
+    Redo,
+    History,
+    Archive,
+    Restore,
+    Run,
+    Completions,
+    Help,
 }
 
 pub trait StrEnum {
-    fn as_str(&self) -> &'static str;
-
     fn from_str(command: &str) -> Option<Self>
     where
         Self: Sized;
 }
 
-impl StrEnum for ArgCommand {
-    fn as_str(&self) -> &'static str {
-        Self::ALL_COMMANDS[*self as usize]
+pub struct CommandHelp {
+    pub name: &'static str,
+    pub syntax: &'static str,
+    pub description: &'static str,
+    pub arguments: &'static [(&'static str, &'static str)],
+    pub examples: &'static [&'static str],
+}
+
+pub const COMMAND_HELP: [CommandHelp; 29] = [
+    CommandHelp {
+        name: "add",
+        syntax: "add [--weight N] <topic>... | -",
+        description: "Adds topics to the end of the list.",
+        arguments: &[
+            (
+                "--weight N",
+                "how likely the topics are to be picked, 1 by default",
+            ),
+            ("-", "reads one topic per line from stdin (arg mode)"),
+        ],
+        examples: &[
+            "add \"Rust traits\" \"Lifetimes\"",
+            "add --weight 3 Ownership",
+        ],
+    },
+    CommandHelp {
+        name: "import",
+        syntax: "import <file> | - [--weight N]",
+        description:
+            "Adds one topic per line of a file or stdin, skipping blank lines and # comments.",
+        arguments: &[
+            ("file", "path of the file to read, - for stdin"),
+            ("--weight N", "how likely the topics are to be picked"),
+        ],
+        examples: &["import topics.txt", "git log --format=%s | rtm import -"],
+    },
+    CommandHelp {
+        name: "pick",
+        syntax: "pick [N] [-y] [--seed S]",
+        description: "Picks topics at random and offers to archive them.",
+        arguments: &[
+            ("N", "number of distinct topics to pick, 1 by default"),
+            ("-y", "archives the picked topics without asking (arg mode)"),
+            ("--seed S", "makes the pick reproducible"),
+        ],
+        examples: &["pick", "pick 3 -y"],
+    },
+    CommandHelp {
+        name: "remove",
+        syntax: "remove <index|range>... | --match [-i|-r] <pattern>",
+        description: "Moves topics to the archive.",
+        arguments: &[
+            ("index", "1-based index, or -N counted from the end"),
+            ("range", "A-B, or A- for everything from A"),
+            ("--match", "removes the topics containing a pattern"),
+            (
+                "-i, -r",
+                "ignore case, or treat the pattern as a regular expression",
+            ),
+        ],
+        examples: &["remove 2 5-7", "remove -1", "remove --match -i draft"],
+    },
+    CommandHelp {
+        name: "edit",
+        syntax: "edit <index> <text>...",
+        description: "Changes the text of a topic.",
+        arguments: &[
+            ("index", "1-based index of the topic"),
+            ("text", "the new text"),
+        ],
+        examples: &["edit 3 Borrow checker"],
+    },
+    CommandHelp {
+        name: "move",
+        syntax: "move <from> <to>",
+        description: "Moves a topic to another position.",
+        arguments: &[("from, to", "1-based indices")],
+        examples: &["move 5 1"],
+    },
+    CommandHelp {
+        name: "swap",
+        syntax: "swap <index> <index>",
+        description: "Swaps the positions of two topics.",
+        arguments: &[("index", "1-based indices")],
+        examples: &["swap 1 2"],
+    },
+    CommandHelp {
+        name: "sort",
+        syntax: "sort [alpha|length|reverse]",
+        description: "Sorts the topics, alphabetically by default.",
+        arguments: &[("order", "alpha, length or reverse")],
+        examples: &["sort", "sort length"],
+    },
+    CommandHelp {
+        name: "dedupe",
+        syntax: "dedupe [exact|normalized]",
+        description: "Archives topics repeating an earlier one.",
+        arguments: &[(
+            "comparison",
+            "normalized ignores case and spacing and is the default, exact does not",
+        )],
+        examples: &["dedupe", "dedupe exact"],
+    },
+    CommandHelp {
+        name: "moveto",
+        syntax: "moveto <index> <list>",
+        description: "Moves a topic to the end of another list.",
+        arguments: &[
+            ("index", "1-based index of the topic"),
+            ("list", "name or index of the list"),
+        ],
+        examples: &["moveto 2 Backlog"],
+    },
+    CommandHelp {
+        name: "copyto",
+        syntax: "copyto <index> <list>",
+        description: "Copies a topic to the end of another list.",
+        arguments: &[
+            ("index", "1-based index of the topic"),
+            ("list", "name or index of the list"),
+        ],
+        examples: &["copyto 2 Backlog"],
+    },
+    CommandHelp {
+        name: "merge",
+        syntax: "merge <list> [--dedupe]",
+        description: "Appends the topics of another list, leaving that list unchanged.",
+        arguments: &[
+            ("list", "name or index of the list"),
+            ("--dedupe", "skips topics already in this list"),
+        ],
+        examples: &["merge \"Team B\" --dedupe"],
+    },
+    CommandHelp {
+        name: "entries",
+        syntax: "entries [--filter [-i|-r] <pattern>]",
+        description: "Prints the topics of the list.",
+        arguments: &[
+            ("--filter", "prints only the topics containing a pattern"),
+            (
+                "-i, -r",
+                "ignore case, or treat the pattern as a regular expression",
+            ),
+        ],
+        examples: &["entries", "entries --filter -i rust"],
+    },
+    CommandHelp {
+        name: "find",
+        syntax: "find [-i|-r] <pattern>",
+        description: "Shows the topics containing a pattern.",
+        arguments: &[
+            ("-i", "ignores case"),
+            ("-r", "treats the pattern as a regular expression"),
+        ],
+        examples: &["find -i rust", "find -r ^[A-Z]"],
+    },
+    CommandHelp {
+        name: "list",
+        syntax: "list",
+        description: "Prints the configured lists.",
+        arguments: &[],
+        examples: &["list"],
+    },
+    CommandHelp {
+        name: "switch",
+        syntax: "switch [<list>]",
+        description: "Opens another list and makes it the default.",
+        arguments: &[(
+            "list",
+            "name or index of the list, asked for when missing in interactive mode",
+        )],
+        examples: &["switch Backlog", "switch 2"],
+    },
+    CommandHelp {
+        name: "undo",
+        syntax: "undo",
+        description: "Reverts the last change.",
+        arguments: &[],
+        examples: &["undo"],
+    },
+    CommandHelp {
+        name: "redo",
+        syntax: "redo",
+        description: "Applies the last reverted change again.",
+        arguments: &[],
+        examples: &["redo"],
+    },
+    CommandHelp {
+        name: "branches",
+        syntax: "branches",
+        description: "Shows the last change of every branch of the undo history.",
+        arguments: &[],
+        examples: &["branches"],
+    },
+    CommandHelp {
+        name: "jump",
+        syntax: "jump <change>",
+        description: "Moves to the state after a change of the undo history.",
+        arguments: &[(
+            "change",
+            "number of the change, 0 for the state before any change",
+        )],
+        examples: &["jump 4", "jump 0"],
+    },
+    CommandHelp {
+        name: "timeline",
+        syntax: "timeline",
+        description: "Shows every change of the undo history.",
+        arguments: &[],
+        examples: &["timeline"],
+    },
+    CommandHelp {
+        name: "refresh",
+        syntax: "refresh",
+        description: "Reads the list again.",
+        arguments: &[],
+        examples: &["refresh"],
+    },
+    CommandHelp {
+        name: "history",
+        syntax: "history [N]",
+        description: "Shows the topics picked so far.",
+        arguments: &[("N", "number of most recent picks to show")],
+        examples: &["history", "history 10"],
+    },
+    CommandHelp {
+        name: "archive",
+        syntax: "archive",
+        description: "Shows the removed topics.",
+        arguments: &[],
+        examples: &["archive"],
+    },
+    CommandHelp {
+        name: "restore",
+        syntax: "restore <index>...",
+        description: "Moves archived topics back to the end of the list.",
+        arguments: &[("index", "1-based index in the archive")],
+        examples: &["restore 1 3"],
+    },
+    CommandHelp {
+        name: "run",
+        syntax: "run <file>",
+        description: "Runs the commands of a file, one per line.",
+        arguments: &[
+            ("file", "path of the script"),
+            ("# comment", "lines starting with # are skipped"),
+            ("set -e", "stops the script at the first failed command"),
+        ],
+        examples: &["run weekly.rtm"],
+    },
+    CommandHelp {
+        name: "completions",
+        syntax: "completions bash|zsh|fish [<name>]",
+        description: "Prints a shell completion script.",
+        arguments: &[
+            ("shell", "bash, zsh or fish"),
+            (
+                "name",
+                "command to complete, the name this program was run by when missing",
+            ),
+        ],
+        examples: &[
+            "source <(rtm completions bash)",
+            "rtm completions fish > ~/.config/fish/completions/rtm.fish",
+        ],
+    },
+    CommandHelp {
+        name: "help",
+        syntax: "help [<command>]",
+        description: "Shows the available commands or the usage of one.",
+        arguments: &[("command", "name of the command")],
+        examples: &["help", "help remove"],
+    },
+    CommandHelp {
+        name: "exit",
+        syntax: "exit",
+        description: "Saves the list and quits.",
+        arguments: &[],
+        examples: &["exit"],
+    },
+];
+
+pub enum HelpTopic {
+    Commands(Vec<&'static CommandHelp>),
+    Command(&'static CommandHelp),
+}
+
+impl CommandHelp {
+    pub fn find(name: &str) -> Option<&'static CommandHelp> {
+        COMMAND_HELP.iter().find(|help| help.name == name)
+    }
+
+    pub fn for_commands(commands: &[&str]) -> Vec<&'static CommandHelp> {
+        commands
+            .iter()
+            .filter_map(|name| Self::find(name))
+            .collect()
+    }
+
+    pub fn topic(
+        args: &[String],
+        commands: &[&'static str],
+        aliases: &HashMap<String, String>,
+    ) -> Result<HelpTopic, CommandResult> {
+        match args {
+            [] => Ok(HelpTopic::Commands(Self::for_commands(commands))),
+            [name] => Some(resolve_command(name, commands, aliases)?)
+                .filter(|command| commands.contains(command))
+                .and_then(Self::find)
+                .map(HelpTopic::Command)
+                .ok_or_else(|| CommandResult::UnknownCommand(format!("Unknown command: {name}"))),
+            _ => Err(CommandResult::Fail(
+                "Incorrect number of arguments".to_string(),
+            )),
+        }
     }
 
+    pub fn completion_words(&self) -> Vec<&'static str> {
+        self.syntax
+            .split_whitespace()
+            .skip(1)
+            .filter(|token| !token.contains('<'))
+            .flat_map(|token| token.trim_matches(['[', ']', '.']).split('|'))
+            .filter(|word| {
+                (word.starts_with('-') && word.len() > 1)
+                    || (!word.is_empty() && word.chars().all(|c| c.is_ascii_lowercase()))
+            })
+            .collect()
+    }
+
+    pub fn argument_position(&self, placeholder: &str) -> Option<usize> {
+        self.syntax
+            .split_whitespace()
+            .skip(1)
+            .filter(|token| token.contains('<'))
+            .position(|token| token.trim_matches(['[', ']', '.']) == placeholder)
+            .map(|index| index + 1)
+    }
+}
+
+/// Resolves what was typed to one of `commands`: a full name, then an alias from the
+/// settings, then a prefix of only one command. Anything else is returned unchanged.
+pub fn resolve_command<'a>(
+    command: &'a str,
+    commands: &[&'static str],
+    aliases: &HashMap<String, String>,
+) -> Result<&'a str, CommandResult> {
+    if commands.contains(&command) {
+        return Ok(command);
+    }
+    if let Some(target) = aliases.get(command) {
+        return match resolve_prefix(target, commands)? {
+            Some(resolved) => Ok(resolved),
+            None => Err(CommandResult::UnknownCommand(format!(
+                "Alias \"{command}\" stands for unknown command: {target}"
+            ))),
+        };
+    }
+    Ok(resolve_prefix(command, commands)?.unwrap_or(command))
+}
+
+fn resolve_prefix(
+    prefix: &str,
+    commands: &[&'static str],
+) -> Result<Option<&'static str>, CommandResult> {
+    if let Some(command) = commands.iter().copied().find(|command| *command == prefix) {
+        return Ok(Some(command));
+    }
+    let matches: Vec<&'static str> = commands
+        .iter()
+        .copied()
+        .filter(|command| !prefix.is_empty() && command.starts_with(prefix))
+        .collect();
+    match matches[..] {
+        [] => Ok(None),
+        [command] => Ok(Some(command)),
+        _ => Err(CommandResult::UnknownCommand(format!(
+            "Ambiguous command: {prefix}, could be {}",
+            matches.join(", ")
+        ))),
+    }
+}
+
+impl StrEnum for ArgCommand {
     fn from_str(command: &str) -> Option<Self>
     where
         Self: Sized,
     {
         match command {
             "add" => Some(ArgCommand::Add),
+            "import" => Some(ArgCommand::Import),
             "pick" => Some(ArgCommand::Pick),
             "remove" => Some(ArgCommand::Remove),
+            "edit" => Some(ArgCommand::Edit),
+            "move" => Some(ArgCommand::Move),
+            "swap" => Some(ArgCommand::Swap),
+            "sort" => Some(ArgCommand::Sort),
+            "dedupe" => Some(ArgCommand::Dedupe),
+            "moveto" => Some(ArgCommand::MoveTo),
+            "copyto" => Some(ArgCommand::CopyTo),
+            "merge" => Some(ArgCommand::Merge),
             "entries" => Some(ArgCommand::Entries),
             "list" => Some(ArgCommand::List),
             "switch" => Some(ArgCommand::Switch),
+            "undo" => Some(ArgCommand::Undo),
+            "redo" => Some(ArgCommand::Redo),
+            "history" => Some(ArgCommand::History),
+            "archive" => Some(ArgCommand::Archive),
+            "restore" => Some(ArgCommand::Restore),
+            "run" => Some(ArgCommand::Run),
+            "completions" => Some(ArgCommand::Completions),
+            "help" => Some(ArgCommand::Help),
             _ => None,
         }
     }
 }
 
 impl ArgCommand {
-    pub const ALL_COMMANDS: [&'static str; 6] =
-        ["add", "pick", "remove", "entries", "list", "switch"];
+    pub const ALL_COMMANDS: [&'static str; 23] = [
+        "add",
+        "import",
+        "pick",
+        "remove",
+        "edit",
+        "move",
+        "swap",
+        "sort",
+        "dedupe",
+        "moveto",
+        "copyto",
+        "merge",
+        "entries",
+        "list",
+        "switch",
+        "undo",
+        "redo",
+        "history",
+        "archive",
+        "restore",
+        "run",
+        "completions",
+        "help",
+    ];
 }
 
 #[derive(Copy, Clone)]
@@ -47,18 +484,31 @@ pub enum RuntimeCommand {
     Add,
     Pick,
     Remove,
+    Edit,
+    Move,
+    Swap,
+    Sort,
+    Dedupe,
+    MoveTo,
+    CopyTo,
+    Merge,
+    Find,
     Undo,
     Redo,
+    Branches,
+    Jump,
+    Timeline,
     Switch,
     Refresh,
+    History,
+    Archive,
+    Restore,
+    Run,
+    Help,
     Exit,
 }
 
 impl StrEnum for RuntimeCommand {
-    fn as_str(&self) -> &'static str {
-        Self::ALL_COMMANDS[*self as usize]
-    }
-
     fn from_str(command: &str) -> Option<Self>
     where
         Self: Sized,
@@ -67,10 +517,27 @@ impl StrEnum for RuntimeCommand {
             "add" => Some(RuntimeCommand::Add),
             "pick" => Some(RuntimeCommand::Pick),
             "remove" => Some(RuntimeCommand::Remove),
+            "edit" => Some(RuntimeCommand::Edit),
+            "move" => Some(RuntimeCommand::Move),
+            "swap" => Some(RuntimeCommand::Swap),
+            "sort" => Some(RuntimeCommand::Sort),
+            "dedupe" => Some(RuntimeCommand::Dedupe),
+            "moveto" => Some(RuntimeCommand::MoveTo),
+            "copyto" => Some(RuntimeCommand::CopyTo),
+            "merge" => Some(RuntimeCommand::Merge),
+            "find" => Some(RuntimeCommand::Find),
             "undo" => Some(RuntimeCommand::Undo),
             "redo" => Some(RuntimeCommand::Redo),
+            "branches" => Some(RuntimeCommand::Branches),
+            "jump" => Some(RuntimeCommand::Jump),
+            "timeline" => Some(RuntimeCommand::Timeline),
             "switch" => Some(RuntimeCommand::Switch),
             "refresh" => Some(RuntimeCommand::Refresh),
+            "history" => Some(RuntimeCommand::History),
+            "archive" => Some(RuntimeCommand::Archive),
+            "restore" => Some(RuntimeCommand::Restore),
+            "run" => Some(RuntimeCommand::Run),
+            "help" => Some(RuntimeCommand::Help),
             "exit" => Some(RuntimeCommand::Exit),
             _ => None,
         }
@@ -78,12 +545,116 @@ impl StrEnum for RuntimeCommand {
 }
 
 impl RuntimeCommand {
-    pub const ALL_COMMANDS: [&'static str; 8] = [
-        "add", "pick", "remove", "undo", "redo", "switch", "refresh", "exit",
+    pub const ALL_COMMANDS: [&'static str; 25] = [
+        "add", "pick", "remove", "edit", "move", "swap", "sort", "dedupe", "moveto", "copyto",
+        "merge", "find", "undo", "redo", "branches", "jump", "timeline", "switch", "refresh",
+        "history", "archive", "restore", "run", "help", "exit",
     ];
 }
 
 pub enum CommandResult {
     Success,
     Fail(String),
+    UnknownCommand(String),
+    ListNotFound(String),
+    ReadFailed(String),
+    WriteFailed(String),
+}
+
+impl CommandResult {
+    pub fn error_message(&self) -> Option<&str> {
+        match self {
+            CommandResult::Success => None,
+            CommandResult::Fail(message)
+            | CommandResult::UnknownCommand(message)
+            | CommandResult::ListNotFound(message)
+            | CommandResult::ReadFailed(message)
+            | CommandResult::WriteFailed(message) => Some(message),
+        }
+    }
+
+    pub fn with_prefix(self, prefix: &str) -> Self {
+        match self {
+            CommandResult::Success => CommandResult::Success,
+            CommandResult::Fail(message) => CommandResult::Fail(format!("{prefix}{message}")),
+            CommandResult::UnknownCommand(message) => {
+                CommandResult::UnknownCommand(format!("{prefix}{message}"))
+            }
+            CommandResult::ListNotFound(message) => {
+                CommandResult::ListNotFound(format!("{prefix}{message}"))
+            }
+            CommandResult::ReadFailed(message) => {
+                CommandResult::ReadFailed(format!("{prefix}{message}"))
+            }
+            CommandResult::WriteFailed(message) => {
+                CommandResult::WriteFailed(format!("{prefix}{message}"))
+            }
+        }
+    }
+
+    pub fn exit_status(&self) -> ExitStatus {
+        match self {
+            CommandResult::Success => ExitStatus::Success,
+            CommandResult::Fail(_) => ExitStatus::BadArguments,
+            CommandResult::UnknownCommand(_) => ExitStatus::UnknownCommand,
+            CommandResult::ListNotFound(_) => ExitStatus::ListNotFound,
+            CommandResult::ReadFailed(_) => ExitStatus::ReadFailure,
+            CommandResult::WriteFailed(_) => ExitStatus::WriteFailure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(alias, command)| (alias.to_string(), command.to_string()))
+            .collect()
+    }
+
+    fn resolve(command: &str, aliases: &HashMap<String, String>) -> Result<String, String> {
+        resolve_command(command, &ArgCommand::ALL_COMMANDS, aliases)
+            .map(str::to_string)
+            .map_err(|result| result.error_message().unwrap_or_default().to_string())
+    }
+
+    #[test]
+    fn exact_names_win_over_aliases() {
+        let aliases = aliases(&[("pick", "remove")]);
+        assert_eq!(resolve("pick", &aliases), Ok("pick".to_string()));
+    }
+
+    #[test]
+    fn aliases_may_stand_for_prefixes() {
+        let aliases = aliases(&[("d", "pi"), ("u", "undo")]);
+        assert_eq!(resolve("d", &aliases), Ok("pick".to_string()));
+        assert_eq!(resolve("u", &aliases), Ok("undo".to_string()));
+    }
+
+    #[test]
+    fn unique_prefixes_are_expanded() {
+        let aliases = aliases(&[]);
+        assert_eq!(resolve("imp", &aliases), Ok("import".to_string()));
+        assert_eq!(resolve("nonsense", &aliases), Ok("nonsense".to_string()));
+    }
+
+    #[test]
+    fn ambiguous_prefixes_fail() {
+        let aliases = aliases(&[("r", "re")]);
+        let message = "Ambiguous command: re, could be remove, redo, restore".to_string();
+        assert_eq!(resolve("re", &aliases), Err(message.clone()));
+        assert_eq!(resolve("r", &aliases), Err(message));
+    }
+
+    #[test]
+    fn aliases_for_unknown_commands_fail() {
+        let aliases = aliases(&[("x", "explode")]);
+        assert_eq!(
+            resolve("x", &aliases),
+            Err("Alias \"x\" stands for unknown command: explode".to_string())
+        );
+    }
 }
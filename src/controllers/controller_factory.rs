@@ -2,19 +2,31 @@ use crate::{
     controllers::{
         arg_controller::ArgController, runtime_controller::RuntimeController, Controller,
     },
+    exit_status::{ExitStatus, Failure},
     models::model::Model,
+    settings::{List, Settings},
     views::View,
 };
 
 pub trait ControllerFactory {
-    fn get_controller(&self, model: Model, view: Box<dyn View>) -> Box<dyn Controller>;
+    fn get_controller(
+        &self,
+        list: List,
+        settings: &Settings,
+        view: Box<dyn View>,
+    ) -> Result<Box<dyn Controller>, Failure>;
 }
 
 pub struct ArgControllerFactory;
 
 impl ControllerFactory for ArgControllerFactory {
-    fn get_controller(&self, model: Model, view: Box<dyn View>) -> Box<dyn Controller> {
-        Box::new(ArgController::new(model, view))
+    fn get_controller(
+        &self,
+        list: List,
+        _settings: &Settings,
+        view: Box<dyn View>,
+    ) -> Result<Box<dyn Controller>, Failure> {
+        Ok(Box::new(ArgController::new(list, view)))
     }
 }
 
@@ -27,8 +39,15 @@ impl ArgControllerFactory {
 pub struct RuntimeControllerFactory;
 
 impl ControllerFactory for RuntimeControllerFactory {
-    fn get_controller(&self, model: Model, view: Box<dyn View>) -> Box<dyn Controller> {
-        Box::new(RuntimeController::new(model, view))
+    fn get_controller(
+        &self,
+        list: List,
+        settings: &Settings,
+        view: Box<dyn View>,
+    ) -> Result<Box<dyn Controller>, Failure> {
+        let model = Model::open(&list, settings)
+            .map_err(|error| Failure::new(ExitStatus::ReadFailure, error))?;
+        Ok(Box::new(RuntimeController::new(model, view)))
     }
 }
 
@@ -1,14 +1,9 @@
 use crate::{
     controllers::{controller_factory::ControllerFactory, Controller},
-    models::{
-        github_gist_topic_writer::GithubGistTopicWriter, local_topic_writer::LocalTopicWriter,
-        model::Model, network_topic_writer::NetworkTopicWriter, topic_handler::TopicHandler,
-        TopicWriter,
-    },
-    settings::{ListType, Settings},
+    exit_status::{ExitStatus, Failure},
+    settings::Settings,
     views::View,
 };
-use anyhow::Result;
 
 pub struct MasterController {
     settings: Settings,
@@ -16,39 +11,46 @@ pub struct MasterController {
 }
 
 impl MasterController {
+    /// A queried list is only used for this run and does not change the settings.
     pub fn new(
         mut settings: Settings,
+        list_query: Option<&str>,
         view: Box<dyn View>,
         controller_factory: impl ControllerFactory,
-    ) -> Self {
-        let documents_path = settings.documents_path().clone();
-        let list_name = settings.open_in().to_string();
-        let list = &settings
-            .get_list(&list_name)
-            .unwrap_or_else(|| settings.get_list_by_index(0));
-
-        let mut topic_writer: Box<dyn TopicWriter> = match list.list_type() {
-            ListType::Local => Box::new(LocalTopicWriter::new(list, &documents_path)),
-            ListType::Network => Box::new(NetworkTopicWriter::new(list)),
-            ListType::GithubGist => Box::new(GithubGistTopicWriter::new(list)),
+    ) -> Result<Self, Failure> {
+        let list = match list_query {
+            Some(query) => settings.get_list(query).ok_or_else(|| {
+                Failure::new(
+                    ExitStatus::ListNotFound,
+                    format!("Couldn't find: \"{query}\" in lists."),
+                )
+            })?,
+            None => {
+                let list_name = settings.open_in().to_string();
+                settings
+                    .get_list(&list_name)
+                    .unwrap_or_else(|| settings.get_list_by_index(0))
+            }
         };
 
-        let topic_handler =
-            TopicHandler::new(&topic_writer.read_list().expect("Could not read topics."));
+        let sub_controller = controller_factory.get_controller(list, &settings, view)?;
 
-        Self {
+        Ok(Self {
             settings,
-            sub_controller: controller_factory
-                .get_controller(Model::new(topic_writer, topic_handler, &list_name), view),
-        }
+            sub_controller,
+        })
     }
 
-    pub fn close(&mut self) -> Result<()> {
-        self.sub_controller.close()?;
-        self.settings.save_settings()
+    pub fn close(&mut self) -> Result<(), Failure> {
+        self.sub_controller
+            .close()
+            .map_err(|error| Failure::new(ExitStatus::WriteFailure, error))?;
+        self.settings
+            .save_settings()
+            .map_err(|error| Failure::new(ExitStatus::SettingsError, error))
     }
 
-    pub fn run(&mut self) {
-        self.sub_controller.run(&mut self.settings);
+    pub fn run(&mut self) -> ExitStatus {
+        self.sub_controller.run(&mut self.settings)
     }
 }
@@ -1,13 +1,14 @@
-use crate::settings::Settings;
+use crate::{exit_status::ExitStatus, settings::Settings};
 
 pub mod arg_controller;
 pub mod commands;
 pub mod controller_factory;
 pub mod master_controller;
 pub mod runtime_controller;
+pub mod script;
 
 pub trait Controller {
-    fn run(&mut self, settings: &mut Settings);
+    fn run(&mut self, settings: &mut Settings) -> ExitStatus;
 
     fn close(&mut self) -> anyhow::Result<()>;
 }
@@ -1,14 +1,17 @@
 use crate::{
     controllers::{
-        commands::{CommandResult, RuntimeCommand, StrEnum},
+        commands::{
+            resolve_command, CommandHelp, CommandResult, HelpTopic, RuntimeCommand, StrEnum,
+        },
+        script::{self, ScriptHost},
         Controller,
     },
+    exit_status::ExitStatus,
     models::{
-        github_gist_topic_writer::GithubGistTopicWriter, local_topic_writer::LocalTopicWriter,
-        model::Model, network_topic_writer::NetworkTopicWriter, topic_handler::TopicHandler,
-        TopicWriter,
+        model::Model, pick_options::PickOptions, topic_change::TopicChange,
+        topic_filter::TopicFilter, undo_redo_handler::UndoNode,
     },
-    settings::{List, ListType, Settings},
+    settings::{List, Settings},
     views::{parsed_command::ParsedCommand, View},
 };
 
@@ -17,14 +20,16 @@ pub struct RuntimeController {
     view: Box<dyn View>,
     las_write_succeeded: bool,
     should_rerender: bool,
+    script_depth: usize,
 }
 
 impl Controller for RuntimeController {
-    fn run(&mut self, settings: &mut Settings) {
+    fn run(&mut self, settings: &mut Settings) -> ExitStatus {
         loop {
             if self.should_rerender() {
                 self.view.render(
-                    self.model.topic_handler.get_topics(),
+                    &self.model.topic_handler.get_entries(),
+                    &self.model.list,
                     self.model.topic_writer.get_banner(),
                     self.model.topic_writer.get_banner_color(),
                 );
@@ -33,7 +38,7 @@ impl Controller for RuntimeController {
                 let result = self
                     .model
                     .topic_writer
-                    .write(self.model.topic_handler.get_topics());
+                    .write(self.model.topic_handler.get_list());
 
                 self.las_write_succeeded = result.is_ok();
             }
@@ -44,23 +49,41 @@ impl Controller for RuntimeController {
                 continue;
             }
 
-            if let CommandResult::Fail(error_message) = self.pass_command(&command, settings) {
-                self.view.print_error(&error_message);
+            if let Some(error_message) = self.pass_command(&command, settings).error_message() {
+                self.view.print_error(error_message);
             }
             if !self.model.topic_handler.can_continue() {
                 break;
             }
         }
+        ExitStatus::Success
     }
 
     fn close(&mut self) -> anyhow::Result<()> {
-        if self.las_write_succeeded && !self.model.topic_handler.is_modified(true) {
-            return Ok(());
+        let result = self.write_list();
+        if let Err(error) = self.model.save_history() {
+            self.view
+                .print_error(&format!("Failed to save history: {error}"));
         }
-        self.model
-            .topic_writer
-            .write(self.model.topic_handler.get_topics())?;
-        self.model.topic_writer.close()
+        result
+    }
+}
+
+impl ScriptHost for RuntimeController {
+    fn script_depth(&mut self) -> &mut usize {
+        &mut self.script_depth
+    }
+
+    fn run_command(&mut self, command: &ParsedCommand, settings: &mut Settings) -> CommandResult {
+        self.pass_command(command, settings)
+    }
+
+    fn report_error(&mut self, message: &str) {
+        self.view.print_error(message);
+    }
+
+    fn has_stopped(&self) -> bool {
+        !self.model.topic_handler.can_continue()
     }
 }
 
@@ -71,48 +94,158 @@ impl RuntimeController {
             view,
             las_write_succeeded: true,
             should_rerender: true,
+            script_depth: 0,
         }
     }
 
+    fn write_list(&mut self) -> anyhow::Result<()> {
+        if self.las_write_succeeded && !self.model.topic_handler.is_modified(true) {
+            return Ok(());
+        }
+        self.model
+            .topic_writer
+            .write(self.model.topic_handler.get_list())?;
+        self.model.topic_writer.close()
+    }
+
     fn pass_command(
         &mut self,
         parsed_command: &ParsedCommand,
         settings: &mut Settings,
     ) -> CommandResult {
-        match RuntimeCommand::from_str(parsed_command.command()) {
+        let command = match resolve_command(
+            parsed_command.command(),
+            &RuntimeCommand::ALL_COMMANDS,
+            settings.aliases(),
+        ) {
+            Ok(command) => command,
+            Err(result) => return result,
+        };
+        match RuntimeCommand::from_str(command) {
             Some(command) => match command {
                 RuntimeCommand::Add => self.model.topic_handler.add_topics(parsed_command.args()),
-                RuntimeCommand::Pick => self.pick_entry(),
+                RuntimeCommand::Pick => self.pick_entry(parsed_command.args()),
                 RuntimeCommand::Remove => self
                     .model
                     .topic_handler
                     .remove_topics(parsed_command.args()),
+                RuntimeCommand::Edit => self.model.topic_handler.edit_topic(parsed_command.args()),
+                RuntimeCommand::Move => self.model.topic_handler.move_topic(parsed_command.args()),
+                RuntimeCommand::Swap => self.model.topic_handler.swap_topics(parsed_command.args()),
+                RuntimeCommand::Sort => self.model.topic_handler.sort_topics(parsed_command.args()),
+                RuntimeCommand::Dedupe => self
+                    .model
+                    .topic_handler
+                    .dedupe_topics(parsed_command.args()),
+                RuntimeCommand::MoveTo => {
+                    self.model
+                        .send_topic(parsed_command.args(), settings, false)
+                }
+                RuntimeCommand::CopyTo => {
+                    self.model.send_topic(parsed_command.args(), settings, true)
+                }
+                RuntimeCommand::Merge => self.model.merge_list(parsed_command.args(), settings),
+                RuntimeCommand::Find => self.find_entries(parsed_command.args()),
                 RuntimeCommand::Undo => self.model.topic_handler.undo(),
                 RuntimeCommand::Redo => self.model.topic_handler.redo(),
+                RuntimeCommand::Branches => {
+                    let topic_history = self.model.topic_handler.get_topic_history();
+                    self.view.print_undo_history(
+                        &topic_history.branch_tips(),
+                        &topic_history.current_path(),
+                    );
+                    CommandResult::Success
+                }
+                RuntimeCommand::Jump => self
+                    .model
+                    .topic_handler
+                    .jump_to_change(parsed_command.args()),
+                RuntimeCommand::Timeline => {
+                    let topic_history = self.model.topic_handler.get_topic_history();
+                    let nodes: Vec<&UndoNode<TopicChange>> = topic_history.nodes().iter().collect();
+                    self.view
+                        .print_undo_history(&nodes, &topic_history.current_path());
+                    CommandResult::Success
+                }
                 RuntimeCommand::Switch => self.switch_list(settings, parsed_command.args()),
-                RuntimeCommand::Refresh => {
-                    self.set_app_state(&settings.get_list(&self.model.list_name).unwrap(), settings)
+                RuntimeCommand::Refresh => self.set_app_state(
+                    &settings.get_list(self.model.list.name()).unwrap(),
+                    settings,
+                ),
+                RuntimeCommand::History => self.show_history(parsed_command.args()),
+                RuntimeCommand::Archive => {
+                    self.view
+                        .print_archive(&self.model.topic_handler.get_archive_entries());
+                    CommandResult::Success
                 }
+                RuntimeCommand::Run => script::run_script(self, parsed_command.args(), settings),
+                RuntimeCommand::Help => self.show_help(parsed_command.args(), settings),
+                RuntimeCommand::Restore => self
+                    .model
+                    .topic_handler
+                    .restore_topics(parsed_command.args()),
                 RuntimeCommand::Exit => self.model.topic_handler.exit(),
             },
-            None => CommandResult::Fail(format!("Unknown command: {}", parsed_command.command())),
+            None => CommandResult::UnknownCommand(format!(
+                "Unknown command: {}",
+                parsed_command.command()
+            )),
         }
     }
 
-    fn pick_entry(&mut self) -> CommandResult {
-        match self.model.topic_handler.pick_random() {
+    fn pick_entry(&mut self, args: &[String]) -> CommandResult {
+        let options = match PickOptions::parse(args) {
+            Ok(options) => options,
+            Err(message) => return CommandResult::Fail(message),
+        };
+        match self.model.topic_handler.pick(&options) {
             CommandResult::Success => (),
             fail => return fail,
         }
-        if let Some(topic) = self.model.topic_handler.get_chosen_topic() {
-            self.view.display_chosen_topic(topic);
-            if self.view.get_input().is_some_and(|p| p.command() == "y") {
-                return self.model.topic_handler.remove_chosen_topic();
+        self.view.display_chosen_topics(
+            &self.model.topic_handler.get_chosen_topics(),
+            &self.model.list,
+        );
+        if options.remove_chosen() || self.view.get_input().is_some_and(|p| p.command() == "y") {
+            return self.model.topic_handler.remove_chosen_topics();
+        }
+        CommandResult::Success
+    }
+
+    fn find_entries(&mut self, args: &[String]) -> CommandResult {
+        match TopicFilter::parse(args) {
+            Ok(filter) => {
+                self.view.render(
+                    &self.model.topic_handler.find_entries(&filter),
+                    &self.model.list,
+                    self.model.topic_writer.get_banner(),
+                    self.model.topic_writer.get_banner_color(),
+                );
+                CommandResult::Success
             }
+            Err(message) => CommandResult::Fail(message),
+        }
+    }
+
+    fn show_help(&mut self, args: &[String], settings: &Settings) -> CommandResult {
+        match CommandHelp::topic(args, &RuntimeCommand::ALL_COMMANDS, settings.aliases()) {
+            Ok(HelpTopic::Commands(commands)) => self.view.print_command_list(&commands),
+            Ok(HelpTopic::Command(help)) => self.view.print_command_help(help),
+            Err(result) => return result,
         }
         CommandResult::Success
     }
 
+    fn show_history(&mut self, args: &[String]) -> CommandResult {
+        match self.model.topic_handler.recent_picks(args) {
+            Ok(records) => {
+                self.view.print_pick_history(records);
+                CommandResult::Success
+            }
+            Err(result) => result,
+        }
+    }
+
     fn switch_list(&mut self, settings: &mut Settings, args: &[String]) -> CommandResult {
         let input = if args.len() == 1 {
             Some(ParsedCommand::parse_from_args(args))
@@ -127,10 +260,13 @@ impl RuntimeController {
             Some(parsed_command) if parsed_command.is_empty() => CommandResult::Success,
             Some(parsed_command) => {
                 if let Some(list) = settings.get_list(parsed_command.command()) {
-                    self.set_app_state(&list, settings);
-                    CommandResult::Success
+                    let result = self.set_app_state(&list, settings);
+                    if let CommandResult::Success = result {
+                        settings.set_open_in_list(&list);
+                    }
+                    result
                 } else {
-                    CommandResult::Fail(format!(
+                    CommandResult::ListNotFound(format!(
                         "Failed to read list: {}",
                         parsed_command.command()
                     ))
@@ -140,26 +276,24 @@ impl RuntimeController {
         }
     }
 
-    fn set_app_state(&mut self, list: &List, settings: &mut Settings) -> CommandResult {
-        let mut new_topic_writer: Box<dyn TopicWriter> = match list.list_type() {
-            ListType::Local => Box::new(LocalTopicWriter::new(list, settings.documents_path())),
-            ListType::Network => Box::new(NetworkTopicWriter::new(list)),
-            ListType::GithubGist => Box::new(GithubGistTopicWriter::new(list)),
-        };
-
-        match new_topic_writer.read_list() {
-            Ok(topics) => {
-                settings.set_open_in_list(list);
+    fn set_app_state(&mut self, list: &List, settings: &Settings) -> CommandResult {
+        let write_result = self.write_list();
+        self.las_write_succeeded = write_result.is_ok();
+        if let Err(error) = write_result {
+            return CommandResult::WriteFailed(format!(
+                "Failed to write {}: {error}",
+                self.model.list.name()
+            ));
+        }
+        _ = self.model.save_history();
+        match Model::open(list, settings) {
+            Ok(model) => {
                 _ = self.model.topic_writer.close();
 
-                self.set_model(Model::new(
-                    new_topic_writer,
-                    TopicHandler::new(topics.as_slice()),
-                    list.name(),
-                ));
+                self.set_model(model);
                 CommandResult::Success
             }
-            Err(error) => CommandResult::Fail(error.to_string()),
+            Err(error) => CommandResult::ReadFailed(error.to_string()),
         }
     }
 
@@ -0,0 +1,101 @@
+use crate::{
+    controllers::commands::CommandResult, settings::Settings, views::parsed_command::ParsedCommand,
+};
+use std::fs;
+
+pub const MAX_SCRIPT_DEPTH: usize = 8;
+
+pub enum ScriptLine {
+    Command(ParsedCommand),
+    /// `set -e` or `set +e`, turning stopping at the first failed command on or off.
+    ExitOnError(bool),
+}
+
+pub struct Script {
+    lines: Vec<(usize, ScriptLine)>,
+}
+
+impl Script {
+    pub fn parse(content: &str) -> Self {
+        let lines = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_number, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(number, line)| {
+                let script_line = match line.split_whitespace().collect::<Vec<&str>>()[..] {
+                    ["set", "-e"] => ScriptLine::ExitOnError(true),
+                    ["set", "+e"] => ScriptLine::ExitOnError(false),
+                    _ => ScriptLine::Command(ParsedCommand::parse_from_line(line)),
+                };
+                (number, script_line)
+            })
+            .collect();
+        Self { lines }
+    }
+
+    pub fn lines(&self) -> &[(usize, ScriptLine)] {
+        &self.lines
+    }
+}
+
+pub trait ScriptHost {
+    fn script_depth(&mut self) -> &mut usize;
+
+    fn run_command(&mut self, command: &ParsedCommand, settings: &mut Settings) -> CommandResult;
+
+    fn report_error(&mut self, message: &str);
+
+    fn has_stopped(&self) -> bool {
+        false
+    }
+}
+
+/// Failed commands are reported and skipped, unless `set -e` is in effect, in which
+/// case the first failure ends the script and becomes its result.
+pub fn run_script(
+    host: &mut impl ScriptHost,
+    args: &[String],
+    settings: &mut Settings,
+) -> CommandResult {
+    let [path] = args else {
+        return CommandResult::Fail("Expected one argument: file".to_string());
+    };
+    if *host.script_depth() >= MAX_SCRIPT_DEPTH {
+        return CommandResult::Fail(format!(
+            "Scripts cannot be nested more than {MAX_SCRIPT_DEPTH} deep"
+        ));
+    }
+    let script = match fs::read_to_string(path) {
+        Ok(content) => Script::parse(&content),
+        Err(error) => return CommandResult::ReadFailed(format!("Failed to read {path}: {error}")),
+    };
+
+    *host.script_depth() += 1;
+    let mut exit_on_error = false;
+    let mut result = CommandResult::Success;
+    for (number, line) in script.lines() {
+        let command = match line {
+            ScriptLine::ExitOnError(value) => {
+                exit_on_error = *value;
+                continue;
+            }
+            ScriptLine::Command(command) => command,
+        };
+        let line_result = host
+            .run_command(command, settings)
+            .with_prefix(&format!("{path}:{number}: "));
+        if let Some(error_message) = line_result.error_message() {
+            if exit_on_error {
+                result = line_result;
+                break;
+            }
+            host.report_error(error_message);
+        }
+        if host.has_stopped() {
+            break;
+        }
+    }
+    *host.script_depth() -= 1;
+    result
+}
@@ -0,0 +1,43 @@
+use std::{fmt::Display, process::ExitCode};
+
+/// Exit codes of the process, so that scripts can tell failures apart.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ExitStatus {
+    Success = 0,
+    UnknownCommand = 2,
+    BadArguments = 3,
+    ListNotFound = 4,
+    ReadFailure = 5,
+    WriteFailure = 6,
+    SettingsError = 7,
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+/// An error that ends the program with the given exit status.
+#[derive(Debug)]
+pub struct Failure {
+    status: ExitStatus,
+    message: String,
+}
+
+impl Failure {
+    pub fn new(status: ExitStatus, message: impl Display) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn status(&self) -> ExitStatus {
+        self.status
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
@@ -1,40 +1,102 @@
 mod controllers;
+mod exit_status;
 mod models;
+mod options;
 mod settings;
 mod views;
 
 use controllers::{
+    commands::{ArgCommand, CommandHelp},
     controller_factory::{ArgControllerFactory, RuntimeControllerFactory},
     master_controller::MasterController,
 };
+use exit_status::{ExitStatus, Failure};
+use options::Options;
 use settings::Settings;
-use std::io::{self, BufReader};
-use views::{arg_view::ArgConsoleView, runtime_view::RuntimeConsoleView};
-
-fn main() {
-    let args: Vec<String> = std::env::args().skip(1).collect();
-    let settings_result = Settings::get_settings();
-    let Ok(settings) = settings_result else {
-        eprintln!("{}", settings_result.unwrap_err());
-        return;
+use std::{
+    io::{self, BufReader},
+    process::ExitCode,
+};
+use views::{arg_view::ArgConsoleView, output_format, runtime_view::RuntimeConsoleView, View};
+
+fn main() -> ExitCode {
+    let options = match Options::parse(std::env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitStatus::BadArguments.into();
+        }
     };
 
+    if options.version() {
+        println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+        return ExitStatus::Success.into();
+    }
+    if options.help() {
+        print_usage(&options);
+        return ExitStatus::Success.into();
+    }
+
+    match run(&options) {
+        Ok(status) => status.into(),
+        Err(failure) => {
+            eprintln!(
+                "{}",
+                output_format::error_message(options.format(), failure.message())
+            );
+            failure.status().into()
+        }
+    }
+}
+
+fn print_usage(options: &Options) {
+    println!("Usage: rtm [options] [command [arguments]] [\\; command [arguments]]...\n");
+    println!("Without a command, opens the list for interactive use.\n");
+    println!("Options:");
+    for (option, description) in Options::ALL_OPTIONS {
+        println!("  {option:<26}{description}");
+    }
+    println!("\nCommands:");
+    ArgConsoleView::new(
+        Vec::new(),
+        options.format(),
+        io::empty(),
+        io::stdout(),
+        io::stderr(),
+    )
+    .print_command_list(&CommandHelp::for_commands(&ArgCommand::ALL_COMMANDS));
+    println!("\nrtm help <command> shows the usage of a command.");
+    println!("Commands can be shortened to a unique prefix or to an alias from the settings.");
+}
+
+fn run(options: &Options) -> Result<ExitStatus, Failure> {
+    let settings =
+        Settings::get_settings().map_err(|error| Failure::new(ExitStatus::SettingsError, error))?;
+
+    let args = options.command_args().to_vec();
     let mut master_controller = if args.is_empty() {
         MasterController::new(
             settings,
+            options.list(),
             Box::new(RuntimeConsoleView::new(BufReader::new(io::stdin()))),
             RuntimeControllerFactory::new(),
-        )
+        )?
     } else {
         MasterController::new(
             settings,
-            Box::new(ArgConsoleView::new(args, io::stdout(), io::stderr())),
+            options.list(),
+            Box::new(ArgConsoleView::new(
+                args,
+                options.format(),
+                io::stdin().lock(),
+                io::stdout(),
+                io::stderr(),
+            )),
             ArgControllerFactory::new(),
-        )
+        )?
     };
-    master_controller.run();
+    let status = master_controller.run();
 
-    if let Err(error) = master_controller.close() {
-        eprintln!("{error}");
-    }
+    master_controller.close()?;
+    Ok(status)
 }
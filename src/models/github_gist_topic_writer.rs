@@ -1,5 +1,8 @@
 use crate::{
-    models::TopicWriter,
+    models::{
+        topic::{self, TopicList},
+        TopicWriter,
+    },
     settings::{BannerColor, List},
 };
 use anyhow::anyhow;
@@ -21,11 +24,11 @@ pub struct GithubGistTopicWriter {
 }
 
 impl TopicWriter for GithubGistTopicWriter {
-    fn write(&mut self, list: &[String]) -> anyhow::Result<()> {
+    fn write(&mut self, list: &TopicList) -> anyhow::Result<()> {
         let payload = json!({
             "files": {
                 self.file_name.clone(): {
-                    "content": list.join("\n")
+                    "content": topic::serialize_topics(list)?
                 }
             }
         });
@@ -50,12 +53,14 @@ impl TopicWriter for GithubGistTopicWriter {
         Ok(())
     }
 
-    fn read_list(&mut self) -> anyhow::Result<Vec<String>> {
-        self.read_gist(&self.gist_id, &self.file_name)
-            .map(|content| {
-                let list: Vec<String> = content.lines().map(|line| line.to_string()).collect();
-                Ok(list)
-            })?
+    fn read_list(&mut self) -> anyhow::Result<TopicList> {
+        let content = self.read_gist(&self.gist_id, &self.file_name)?;
+
+        let (list, is_legacy) = topic::parse_topics(&content);
+        if is_legacy {
+            _ = self.write(&list);
+        }
+        Ok(list)
     }
 
     fn get_banner(&self) -> &str {
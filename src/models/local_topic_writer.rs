@@ -1,10 +1,12 @@
 use crate::{
-    models::TopicWriter,
+    models::{
+        topic::{self, TopicList},
+        TopicWriter,
+    },
     settings::{BannerColor, List, SETTINGS_DIR_NAME},
 };
 use std::{
     fs,
-    io::{self, BufRead, Write},
     path::{Path, PathBuf},
 };
 
@@ -17,11 +19,8 @@ pub struct LocalTopicWriter {
 }
 
 impl TopicWriter for LocalTopicWriter {
-    fn write(&mut self, list: &[String]) -> anyhow::Result<()> {
-        let mut file: fs::File = fs::File::create(&self.topics_file_path)?;
-        for line in list {
-            writeln!(file, "{}", line)?;
-        }
+    fn write(&mut self, list: &TopicList) -> anyhow::Result<()> {
+        fs::write(&self.topics_file_path, topic::serialize_topics(list)?)?;
         Ok(())
     }
 
@@ -30,16 +29,15 @@ impl TopicWriter for LocalTopicWriter {
         Ok(())
     }
 
-    fn read_list(&mut self) -> anyhow::Result<Vec<String>> {
+    fn read_list(&mut self) -> anyhow::Result<TopicList> {
         self.check_source_exist();
-        let file = fs::File::open(&self.topics_file_path)?;
-        let reader = io::BufReader::new(file);
+        let content = fs::read_to_string(&self.topics_file_path)?;
 
-        let mut vec = Vec::new();
-        for line in reader.lines() {
-            vec.push(line?);
+        let (list, is_legacy) = topic::parse_topics(&content);
+        if is_legacy {
+            self.write(&list)?;
         }
-        Ok(vec)
+        Ok(list)
     }
 
     fn get_banner(&self) -> &str {
@@ -2,17 +2,22 @@ pub mod github_gist_topic_writer;
 pub mod local_topic_writer;
 pub mod model;
 pub mod network_topic_writer;
+pub mod pick_history;
+pub mod pick_options;
+pub mod topic;
+pub mod topic_change;
+pub mod topic_filter;
 pub mod topic_handler;
 pub mod undo_redo_handler;
 
-use crate::settings::BannerColor;
+use crate::{models::topic::TopicList, settings::BannerColor};
 
 pub trait TopicWriter {
-    fn write(&mut self, list: &[String]) -> anyhow::Result<()>;
+    fn write(&mut self, list: &TopicList) -> anyhow::Result<()>;
 
     fn close(&self) -> anyhow::Result<()>;
 
-    fn read_list(&mut self) -> anyhow::Result<Vec<String>>;
+    fn read_list(&mut self) -> anyhow::Result<TopicList>;
 
     fn get_banner(&self) -> &str;
 
@@ -1,21 +1,158 @@
-use crate::models::{topic_handler::TopicHandler, TopicWriter};
+use crate::{
+    controllers::commands::CommandResult,
+    models::{
+        github_gist_topic_writer::GithubGistTopicWriter, local_topic_writer::LocalTopicWriter,
+        network_topic_writer::NetworkTopicWriter, pick_history::PickHistory, topic,
+        topic_handler::TopicHandler, undo_redo_handler::UndoRedoHandler, TopicWriter,
+    },
+    settings::{List, ListType, Settings},
+};
+use anyhow::Result;
+use std::path::PathBuf;
+
+const PICK_HISTORY_DIR_NAME: &str = "history";
+const UNDO_HISTORY_DIR_NAME: &str = "undo";
 
 pub struct Model {
     pub topic_writer: Box<dyn TopicWriter>,
     pub topic_handler: TopicHandler,
-    pub list_name: String,
+    pub list: List,
+    pick_history_path: PathBuf,
+    undo_history_path: PathBuf,
 }
 
 impl Model {
-    pub fn new(
-        topic_writer: Box<dyn TopicWriter>,
-        topic_handler: TopicHandler,
-        list_name: &str,
-    ) -> Self {
-        Self {
+    pub fn open(list: &List, settings: &Settings) -> Result<Self> {
+        let mut topic_writer = Self::open_writer(list, settings);
+        let pick_history_path = settings.list_data_path(list.name(), PICK_HISTORY_DIR_NAME);
+        let undo_history_path = settings.list_data_path(list.name(), UNDO_HISTORY_DIR_NAME);
+
+        let mut topic_handler = TopicHandler::with_seed(topic_writer.read_list()?, list.seed());
+        topic_handler.set_pick_history(PickHistory::load(&pick_history_path).unwrap_or_default());
+        topic_handler.set_no_repeat(list.no_repeat());
+        topic_handler.set_allow_duplicates(list.allow_duplicates());
+        if let Ok(topic_history) = UndoRedoHandler::load(&undo_history_path) {
+            topic_handler.set_topic_history(topic_history);
+        }
+        topic_handler.set_max_undo_depth(settings.undo_depth());
+
+        Ok(Self {
             topic_writer,
             topic_handler,
-            list_name: list_name.to_string(),
+            list: list.clone(),
+            pick_history_path,
+            undo_history_path,
+        })
+    }
+
+    pub fn open_writer(list: &List, settings: &Settings) -> Box<dyn TopicWriter> {
+        match list.list_type() {
+            ListType::Local => Box::new(LocalTopicWriter::new(list, settings.documents_path())),
+            ListType::Network => Box::new(NetworkTopicWriter::new(list)),
+            ListType::GithubGist => Box::new(GithubGistTopicWriter::new(list)),
+        }
+    }
+
+    /// Nothing changes here unless the other list was written successfully.
+    pub fn send_topic(
+        &mut self,
+        args: &[String],
+        settings: &mut Settings,
+        keep: bool,
+    ) -> CommandResult {
+        let [str_index, list_query @ ..] = args else {
+            return CommandResult::Fail("Missing arguments: index, list".to_string());
+        };
+        if list_query.is_empty() {
+            return CommandResult::Fail("Missing argument: list".to_string());
+        }
+        let list_query = list_query.join(" ");
+        let Some(target) = settings.get_list(&list_query) else {
+            return CommandResult::ListNotFound(format!(
+                "Couldn't find: \"{list_query}\" in lists."
+            ));
+        };
+        if target.name() == self.list.name() {
+            return CommandResult::Fail(format!("Topic is already in: {}", self.list.name()));
+        }
+        let Some(topic) = self.topic_handler.get_topic(str_index) else {
+            return CommandResult::Fail(format!("Wrong argument: {str_index}"));
+        };
+
+        let mut target_writer = Self::open_writer(&target, settings);
+        let mut target_list = match target_writer.read_list() {
+            Ok(target_list) => target_list,
+            Err(error) => {
+                return CommandResult::ReadFailed(format!(
+                    "Failed to read {}: {error}",
+                    target.name()
+                ))
+            }
+        };
+        if !target.allow_duplicates()
+            && target_list.topics.iter().any(|other| {
+                topic::normalize_text(other.text()) == topic::normalize_text(topic.text())
+            })
+        {
+            return CommandResult::Fail(format!(
+                "Duplicate topic in {}: {}",
+                target.name(),
+                topic.text()
+            ));
+        }
+        let mut topic = topic.clone();
+        topic.set_id(topic::next_id(&target_list));
+        target_list.topics.push(topic);
+        if let Err(error) = target_writer
+            .write(&target_list)
+            .and_then(|_| target_writer.close())
+        {
+            return CommandResult::WriteFailed(format!(
+                "Failed to write {}: {error}",
+                target.name()
+            ));
+        }
+
+        if keep {
+            CommandResult::Success
+        } else {
+            self.topic_handler.take_topic(str_index)
         }
     }
+
+    pub fn merge_list(&mut self, args: &[String], settings: &mut Settings) -> CommandResult {
+        let dedupe = args.iter().any(|arg| arg == "--dedupe");
+        let list_query = args
+            .iter()
+            .filter(|arg| *arg != "--dedupe")
+            .map(String::as_str)
+            .collect::<Vec<&str>>()
+            .join(" ");
+        if list_query.is_empty() {
+            return CommandResult::Fail("Missing argument: list".to_string());
+        }
+        let Some(source) = settings.get_list(&list_query) else {
+            return CommandResult::ListNotFound(format!(
+                "Couldn't find: \"{list_query}\" in lists."
+            ));
+        };
+        if source.name() == self.list.name() {
+            return CommandResult::Fail("Cannot merge a list into itself".to_string());
+        }
+        match Self::open_writer(&source, settings).read_list() {
+            Ok(source_list) => self.topic_handler.merge_topics(&source_list.topics, dedupe),
+            Err(error) => {
+                CommandResult::ReadFailed(format!("Failed to read {}: {error}", source.name()))
+            }
+        }
+    }
+
+    pub fn save_history(&mut self) -> Result<()> {
+        self.topic_handler
+            .get_pick_history_mut()
+            .save(&self.pick_history_path)?;
+        self.topic_handler
+            .get_topic_history_mut()
+            .save(&self.undo_history_path)
+    }
 }
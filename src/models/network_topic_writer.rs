@@ -1,5 +1,8 @@
 use crate::{
-    models::TopicWriter,
+    models::{
+        topic::{self, TopicList},
+        TopicWriter,
+    },
     settings::{BannerColor, List},
 };
 use anyhow::anyhow;
@@ -14,7 +17,7 @@ pub struct NetworkTopicWriter {
 }
 
 impl TopicWriter for NetworkTopicWriter {
-    fn write(&mut self, list: &[String]) -> anyhow::Result<()> {
+    fn write(&mut self, list: &TopicList) -> anyhow::Result<()> {
         self.put_data(list, &self.endpoint_url)
     }
 
@@ -22,12 +25,14 @@ impl TopicWriter for NetworkTopicWriter {
         Ok(())
     }
 
-    fn read_list(&mut self) -> anyhow::Result<Vec<String>> {
+    fn read_list(&mut self) -> anyhow::Result<TopicList> {
         let response = self.client.get(&self.endpoint_url).send()?;
 
         if response.status().is_success() {
-            let text = response.text()?;
-            let list: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+            let (list, is_legacy) = topic::parse_topics(&response.text()?);
+            if is_legacy {
+                _ = self.write(&list);
+            }
             Ok(list)
         } else {
             Err(anyhow!(format!(
@@ -58,13 +63,13 @@ impl NetworkTopicWriter {
         }
     }
 
-    fn put_data(&self, list: &[String], url: &str) -> anyhow::Result<()> {
+    fn put_data(&self, list: &TopicList, url: &str) -> anyhow::Result<()> {
         let response = self
             .client
             .put(url)
-            .body(list.join("\n"))
+            .body(topic::serialize_topics(list)?)
             .send()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+            .map_err(|e| io::Error::other(e.to_string()))?;
 
         if response.status().is_success() {
             Ok(())
@@ -0,0 +1,84 @@
+use crate::models::topic::Topic;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickRecord {
+    picked_at: DateTime<Utc>,
+    topic: Topic,
+    removed: bool,
+}
+
+impl PickRecord {
+    pub fn picked_at(&self) -> &DateTime<Utc> {
+        &self.picked_at
+    }
+
+    pub fn topic(&self) -> &Topic {
+        &self.topic
+    }
+
+    pub fn removed(&self) -> bool {
+        self.removed
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PickHistory {
+    records: Vec<PickRecord>,
+    #[serde(skip)]
+    is_modified: bool,
+}
+
+impl PickHistory {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        if !self.is_modified {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        self.is_modified = false;
+        Ok(())
+    }
+
+    pub fn records(&self) -> &[PickRecord] {
+        &self.records
+    }
+
+    pub fn push(&mut self, topic: Topic) {
+        self.records.push(PickRecord {
+            picked_at: Utc::now(),
+            topic,
+            removed: false,
+        });
+        self.is_modified = true;
+    }
+
+    pub fn mark_last_removed(&mut self, count: usize) {
+        for record in self.records.iter_mut().rev().take(count) {
+            record.removed = true;
+            self.is_modified = true;
+        }
+    }
+
+    pub fn recent_ids(&self, count: usize) -> Vec<u64> {
+        self.records
+            .iter()
+            .rev()
+            .take(count)
+            .map(|record| record.topic.id())
+            .collect()
+    }
+}
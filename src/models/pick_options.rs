@@ -0,0 +1,43 @@
+pub struct PickOptions {
+    count: usize,
+    seed: Option<u64>,
+    remove_chosen: bool,
+}
+
+impl PickOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = PickOptions {
+            count: 1,
+            seed: None,
+            remove_chosen: false,
+        };
+        let mut args_iter = args.iter();
+        while let Some(arg) = args_iter.next() {
+            match arg.as_str() {
+                "-y" => options.remove_chosen = true,
+                "--seed" => match args_iter.next().map(|seed| seed.parse::<u64>()) {
+                    Some(Ok(seed)) => options.seed = Some(seed),
+                    Some(Err(_)) => return Err("Seed must be a number".to_string()),
+                    None => return Err("Missing argument: seed".to_string()),
+                },
+                _ => match arg.parse::<usize>() {
+                    Ok(count) => options.count = count,
+                    Err(_) => return Err(format!("Incorrect argument: {arg}")),
+                },
+            }
+        }
+        Ok(options)
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn remove_chosen(&self) -> bool {
+        self.remove_chosen
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Topic {
+    id: u64,
+    text: String,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    priority: u8,
+    #[serde(default = "default_weight")]
+    weight: u32,
+}
+
+impl Topic {
+    pub fn new(id: u64, text: &str) -> Self {
+        Self {
+            id,
+            text: text.to_string(),
+            created_at: Utc::now(),
+            tags: Vec::new(),
+            notes: String::new(),
+            priority: 0,
+            weight: default_weight(),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: u64) {
+        self.id = id;
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn set_weight(&mut self, weight: u32) {
+        self.weight = weight;
+    }
+}
+
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedTopic {
+    archived_at: DateTime<Utc>,
+    topic: Topic,
+}
+
+impl ArchivedTopic {
+    pub fn new(topic: Topic) -> Self {
+        Self {
+            archived_at: Utc::now(),
+            topic,
+        }
+    }
+
+    pub fn archived_at(&self) -> &DateTime<Utc> {
+        &self.archived_at
+    }
+
+    pub fn topic(&self) -> &Topic {
+        &self.topic
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TopicList {
+    pub topics: Vec<Topic>,
+    #[serde(default)]
+    pub archive: Vec<ArchivedTopic>,
+}
+
+pub fn next_id(list: &TopicList) -> u64 {
+    list.topics
+        .iter()
+        .chain(list.archive.iter().map(ArchivedTopic::topic))
+        .map(Topic::id)
+        .max()
+        .map_or(1, |id| id + 1)
+}
+
+/// Older lists are newline-separated text; they are reported as legacy so the caller
+/// can write them back in the current format.
+pub fn parse_topics(content: &str) -> (TopicList, bool) {
+    if let Ok(list) = serde_json::from_str::<TopicList>(content) {
+        return (list, false);
+    }
+    let topics: Vec<Topic> = content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| Topic::new(index as u64 + 1, line))
+        .collect();
+    let is_legacy = !topics.is_empty();
+    (
+        TopicList {
+            topics,
+            archive: Vec::new(),
+        },
+        is_legacy,
+    )
+}
+
+/// FNV-1a checksum of the serialized list, stable across runs.
+pub fn checksum(list: &TopicList) -> u64 {
+    serde_json::to_vec(list)
+        .unwrap_or_default()
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+        })
+}
+
+pub fn serialize_topics(list: &TopicList) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(list)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(list: &TopicList) -> Vec<&str> {
+        list.topics.iter().map(Topic::text).collect()
+    }
+
+    #[test]
+    fn newline_lists_are_legacy() {
+        let (list, is_legacy) = parse_topics("first\nsecond topic\nthird\n");
+        assert!(is_legacy);
+        assert_eq!(texts(&list), vec!["first", "second topic", "third"]);
+        assert_eq!(
+            list.topics.iter().map(Topic::id).collect::<Vec<u64>>(),
+            vec![1, 2, 3]
+        );
+        assert!(list.archive.is_empty());
+    }
+
+    #[test]
+    fn empty_content_is_an_empty_list() {
+        let (list, is_legacy) = parse_topics("");
+        assert!(!is_legacy);
+        assert_eq!(list, TopicList::default());
+    }
+
+    #[test]
+    fn serialized_lists_parse_back() {
+        let mut topic = Topic::new(7, "seven");
+        topic.set_weight(3);
+        let list = TopicList {
+            topics: vec![Topic::new(2, "two"), topic.clone()],
+            archive: vec![ArchivedTopic::new(topic)],
+        };
+        let (parsed, is_legacy) = parse_topics(&serialize_topics(&list).unwrap());
+        assert!(!is_legacy);
+        assert_eq!(parsed, list);
+        assert_eq!(next_id(&parsed), 8);
+    }
+
+    #[test]
+    fn checksum_follows_the_content() {
+        let (list, _is_legacy) = parse_topics("a\nb\n");
+        let (parsed, _is_legacy) = parse_topics(&serialize_topics(&list).unwrap());
+        assert_eq!(checksum(&parsed), checksum(&list));
+
+        let mut edited = list.clone();
+        edited.topics[1].set_weight(5);
+        assert_ne!(checksum(&edited), checksum(&list));
+    }
+}
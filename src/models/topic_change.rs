@@ -0,0 +1,110 @@
+use crate::models::topic::{ArchivedTopic, Topic, TopicList};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "operation")]
+pub enum TopicEdit {
+    Insert {
+        index: usize,
+        topic: Topic,
+    },
+    Remove {
+        index: usize,
+        topic: Topic,
+    },
+    Replace {
+        index: usize,
+        old: Topic,
+        new: Topic,
+    },
+    ArchiveInsert {
+        index: usize,
+        entry: ArchivedTopic,
+    },
+    ArchiveRemove {
+        index: usize,
+        entry: ArchivedTopic,
+    },
+}
+
+impl TopicEdit {
+    fn apply(&self, list: &mut TopicList) {
+        match self {
+            TopicEdit::Insert { index, topic } => list.topics.insert(*index, topic.clone()),
+            TopicEdit::Remove { index, .. } => {
+                list.topics.remove(*index);
+            }
+            TopicEdit::Replace { index, new, .. } => list.topics[*index] = new.clone(),
+            TopicEdit::ArchiveInsert { index, entry } => list.archive.insert(*index, entry.clone()),
+            TopicEdit::ArchiveRemove { index, .. } => {
+                list.archive.remove(*index);
+            }
+        }
+    }
+
+    fn revert(&self, list: &mut TopicList) {
+        match self {
+            TopicEdit::Insert { index, .. } => {
+                list.topics.remove(*index);
+            }
+            TopicEdit::Remove { index, topic } => list.topics.insert(*index, topic.clone()),
+            TopicEdit::Replace { index, old, .. } => list.topics[*index] = old.clone(),
+            TopicEdit::ArchiveInsert { index, .. } => {
+                list.archive.remove(*index);
+            }
+            TopicEdit::ArchiveRemove { index, entry } => list.archive.insert(*index, entry.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TopicChange {
+    edits: Vec<TopicEdit>,
+}
+
+impl TopicChange {
+    pub fn new(edits: Vec<TopicEdit>) -> Self {
+        Self { edits }
+    }
+
+    pub fn apply(&self, list: &mut TopicList) {
+        for edit in &self.edits {
+            edit.apply(list);
+        }
+    }
+
+    pub fn revert(&self, list: &mut TopicList) {
+        for edit in self.edits.iter().rev() {
+            edit.revert(list);
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let (mut added, mut removed, mut edited, mut archived, mut restored) = (0, 0, 0, 0, 0);
+        for edit in &self.edits {
+            match edit {
+                TopicEdit::Insert { .. } => added += 1,
+                TopicEdit::Remove { .. } => removed += 1,
+                TopicEdit::Replace { .. } => edited += 1,
+                TopicEdit::ArchiveInsert { .. } => archived += 1,
+                TopicEdit::ArchiveRemove { .. } => restored += 1,
+            }
+        }
+        let parts: Vec<String> = [
+            ("added", added),
+            ("removed", removed),
+            ("edited", edited),
+            ("archived", archived),
+            ("restored", restored),
+        ]
+        .into_iter()
+        .filter(|(_label, count)| *count > 0)
+        .map(|(label, count)| format!("{label} {count}"))
+        .collect();
+        if parts.is_empty() {
+            "no changes".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
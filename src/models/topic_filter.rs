@@ -0,0 +1,38 @@
+use crate::models::topic::Topic;
+use regex::Regex;
+
+pub enum TopicFilter {
+    Substring(String),
+    IgnoreCase(String),
+    Regex(Regex),
+}
+
+impl TopicFilter {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let (flag, pattern) = match args {
+            [flag, pattern @ ..] if flag == "-i" || flag == "-r" => (Some(flag.as_str()), pattern),
+            pattern => (None, pattern),
+        };
+        let pattern = pattern.join(" ");
+        if pattern.is_empty() {
+            return Err("Missing argument: pattern".to_string());
+        }
+        match flag {
+            Some("-i") => Ok(TopicFilter::IgnoreCase(pattern.to_lowercase())),
+            Some(_) => Regex::new(&pattern)
+                .map(TopicFilter::Regex)
+                .map_err(|error| format!("Invalid pattern: {error}")),
+            None => Ok(TopicFilter::Substring(pattern)),
+        }
+    }
+
+    pub fn matches(&self, topic: &Topic) -> bool {
+        match self {
+            TopicFilter::Substring(pattern) => topic.text().contains(pattern.as_str()),
+            TopicFilter::IgnoreCase(pattern) => {
+                topic.text().to_lowercase().contains(pattern.as_str())
+            }
+            TopicFilter::Regex(regex) => regex.is_match(topic.text()),
+        }
+    }
+}
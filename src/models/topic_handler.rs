@@ -1,29 +1,101 @@
-use crate::{controllers::commands::CommandResult, models::undo_redo_handler::UndoRedoHandler};
-use rand::{rngs::ThreadRng, Rng};
+use crate::{
+    controllers::commands::CommandResult,
+    models::{
+        pick_history::{PickHistory, PickRecord},
+        pick_options::PickOptions,
+        topic::{self, ArchivedTopic, Topic, TopicList},
+        topic_change::{TopicChange, TopicEdit},
+        topic_filter::TopicFilter,
+        undo_redo_handler::{HistoryStep, UndoRedoHandler, DEFAULT_MAX_DEPTH},
+    },
+};
+use rand::{distributions::WeightedIndex, prelude::Distribution, rngs::StdRng, Rng, SeedableRng};
+use std::{collections::HashSet, ops::RangeInclusive};
 
-pub struct TopicHandler {
-    topic_history: UndoRedoHandler<Vec<String>>,
-    state: Vec<String>,
+pub struct TopicHandler<R: Rng = StdRng> {
+    topic_history: UndoRedoHandler<TopicChange>,
+    state: TopicList,
     is_modified: bool,
     can_continue: bool,
-    chosen_topic: Option<(String, usize)>,
-    rng: ThreadRng,
+    chosen_topics: Vec<(Topic, usize)>,
+    pick_history: PickHistory,
+    no_repeat: usize,
+    allow_duplicates: bool,
+    rng: R,
 }
 
-impl TopicHandler {
-    pub fn new(state: &[String]) -> Self {
-        let mut undo_redo_handler: UndoRedoHandler<Vec<String>> = UndoRedoHandler::new();
-        undo_redo_handler.add_new_node(state.to_vec());
+impl TopicHandler<StdRng> {
+    pub fn with_seed(state: TopicList, seed: Option<u64>) -> Self {
+        Self::new(
+            state,
+            seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+        )
+    }
+}
+
+impl<R: Rng + SeedableRng> TopicHandler<R> {
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = R::seed_from_u64(seed);
+    }
+
+    pub fn pick(&mut self, options: &PickOptions) -> CommandResult {
+        if let Some(seed) = options.seed() {
+            self.reseed(seed);
+        }
+        self.pick_random(options.count())
+    }
+}
+
+impl<R: Rng> TopicHandler<R> {
+    pub fn new(state: TopicList, rng: R) -> Self {
+        let mut undo_redo_handler: UndoRedoHandler<TopicChange> =
+            UndoRedoHandler::new(DEFAULT_MAX_DEPTH);
+        undo_redo_handler.set_state_checksum(topic::checksum(&state));
         TopicHandler {
-            state: state.to_vec(),
+            state,
             is_modified: false,
             can_continue: true,
-            rng: rand::thread_rng(),
+            rng,
             topic_history: undo_redo_handler,
-            chosen_topic: None,
+            chosen_topics: Vec::new(),
+            pick_history: PickHistory::default(),
+            no_repeat: 0,
+            allow_duplicates: true,
         }
     }
 
+    /// Continues an undo history saved by a previous session, unless the list was
+    /// changed elsewhere since then and the saved changes no longer apply to it.
+    pub fn set_topic_history(&mut self, topic_history: UndoRedoHandler<TopicChange>) {
+        if topic_history.state_checksum() == topic::checksum(&self.state) {
+            self.topic_history = topic_history;
+        }
+    }
+
+    pub fn set_max_undo_depth(&mut self, max_depth: usize) {
+        self.topic_history.set_max_depth(max_depth);
+    }
+
+    pub fn get_topic_history(&self) -> &UndoRedoHandler<TopicChange> {
+        &self.topic_history
+    }
+
+    pub fn get_topic_history_mut(&mut self) -> &mut UndoRedoHandler<TopicChange> {
+        &mut self.topic_history
+    }
+
+    pub fn set_pick_history(&mut self, pick_history: PickHistory) {
+        self.pick_history = pick_history;
+    }
+
+    pub fn set_no_repeat(&mut self, no_repeat: usize) {
+        self.no_repeat = no_repeat;
+    }
+
+    pub fn set_allow_duplicates(&mut self, allow_duplicates: bool) {
+        self.allow_duplicates = allow_duplicates;
+    }
+
     pub fn is_modified(&mut self, update: bool) -> bool {
         let changed = self.is_modified;
         if update {
@@ -36,16 +108,57 @@ impl TopicHandler {
         self.can_continue
     }
 
-    pub fn get_topics(&self) -> &[String] {
-        self.state.as_slice()
+    pub fn get_list(&self) -> &TopicList {
+        &self.state
     }
 
-    pub fn get_chosen_topic(&self) -> Option<&String> {
-        if let Some((ref topic, _index)) = self.chosen_topic {
-            Some(topic)
-        } else {
-            None
-        }
+    pub fn get_archive_entries(&self) -> Vec<(usize, &ArchivedTopic)> {
+        self.state.archive.iter().enumerate().collect()
+    }
+
+    pub fn get_topic(&self, str_index: &str) -> Option<&Topic> {
+        self.parse_index(str_index)
+            .map(|index| &self.state.topics[index])
+    }
+
+    pub fn get_entries(&self) -> Vec<(usize, &Topic)> {
+        self.state.topics.iter().enumerate().collect()
+    }
+
+    pub fn find_entries(&self, filter: &TopicFilter) -> Vec<(usize, &Topic)> {
+        self.state
+            .topics
+            .iter()
+            .enumerate()
+            .filter(|(_index, topic)| filter.matches(topic))
+            .collect()
+    }
+
+    pub fn recent_picks(&self, args: &[String]) -> Result<&[PickRecord], CommandResult> {
+        let records = self.pick_history.records();
+        let count = match args {
+            [] => records.len(),
+            [count] => count
+                .parse::<usize>()
+                .map_err(|_| CommandResult::Fail(format!("Incorrect argument: {count}")))?,
+            _ => {
+                return Err(CommandResult::Fail(
+                    "Incorrect number of arguments".to_string(),
+                ))
+            }
+        };
+        Ok(&records[records.len().saturating_sub(count)..])
+    }
+
+    pub fn get_pick_history_mut(&mut self) -> &mut PickHistory {
+        &mut self.pick_history
+    }
+
+    pub fn get_chosen_topics(&self) -> Vec<(usize, &Topic)> {
+        self.chosen_topics
+            .iter()
+            .map(|(topic, index)| (*index, topic))
+            .collect()
     }
 
     pub fn exit(&mut self) -> CommandResult {
@@ -53,75 +166,376 @@ impl TopicHandler {
         CommandResult::Success
     }
 
+    /// Nothing is removed unless every argument is valid.
     pub fn remove_topics(&mut self, args: &[String]) -> CommandResult {
-        if args.is_empty() {
-            return CommandResult::Fail("Missing arguments: indices".to_string());
-        }
-        let mut indices: Vec<usize> = vec![0; args.len()];
-        for (i, str_index) in args.iter().enumerate() {
-            if let Ok(index) = str_index.parse::<usize>() {
-                if index < self.state.len() + 1 {
-                    indices[i] = index - 1;
-                    continue;
+        let indices: Vec<usize> = match args {
+            [] => return CommandResult::Fail("Missing arguments: indices".to_string()),
+            [flag, filter_args @ ..] if flag == "--match" => {
+                let filter = match TopicFilter::parse(filter_args) {
+                    Ok(filter) => filter,
+                    Err(message) => return CommandResult::Fail(message),
+                };
+                let indices: Vec<usize> = self
+                    .find_entries(&filter)
+                    .into_iter()
+                    .map(|(index, _topic)| index)
+                    .collect();
+                if indices.is_empty() {
+                    return CommandResult::Fail(format!(
+                        "No topics match: {}",
+                        filter_args.join(" ")
+                    ));
                 }
+                indices
             }
-            return CommandResult::Fail(format!("Wrong argument: {}", args[i]));
+            _ => {
+                let mut indices = Vec::new();
+                let mut wrong_args: Vec<&str> = Vec::new();
+                for arg in args {
+                    match self.parse_index_range(arg) {
+                        Some(range) => indices.extend(range),
+                        None => wrong_args.push(arg),
+                    }
+                }
+                if !wrong_args.is_empty() {
+                    return CommandResult::Fail(format!(
+                        "Wrong arguments: {} (list has {} topics)",
+                        wrong_args.join(", "),
+                        self.state.topics.len()
+                    ));
+                }
+                indices
+            }
+        };
+        self.commit_change(self.removal_change(indices));
+        CommandResult::Success
+    }
+
+    pub fn take_topic(&mut self, str_index: &str) -> CommandResult {
+        let Some(index) = self.parse_index(str_index) else {
+            return CommandResult::Fail(format!("Wrong argument: {str_index}"));
+        };
+        self.commit_change(TopicChange::new(vec![TopicEdit::Remove {
+            index,
+            topic: self.state.topics[index].clone(),
+        }]));
+        CommandResult::Success
+    }
+
+    pub fn edit_topic(&mut self, args: &[String]) -> CommandResult {
+        let [str_index, text @ ..] = args else {
+            return CommandResult::Fail("Missing arguments: index, text".to_string());
+        };
+        let Some(index) = self.parse_index(str_index) else {
+            return CommandResult::Fail(format!("Wrong argument: {str_index}"));
+        };
+        let text = text.join(" ");
+        if text.trim().is_empty() {
+            return CommandResult::Fail("Missing argument: text".to_string());
+        }
+        let old = self.state.topics[index].clone();
+        let mut new = old.clone();
+        new.set_text(&text);
+        self.commit_change(TopicChange::new(vec![TopicEdit::Replace {
+            index,
+            old,
+            new,
+        }]));
+        CommandResult::Success
+    }
+
+    pub fn move_topic(&mut self, args: &[String]) -> CommandResult {
+        let [from, to] = args else {
+            return CommandResult::Fail("Expected two arguments: from, to".to_string());
+        };
+        let (Some(from), Some(to)) = (self.parse_index(from), self.parse_index(to)) else {
+            return CommandResult::Fail(format!("Wrong arguments: {from} {to}"));
+        };
+        if from == to {
+            return CommandResult::Success;
+        }
+        let topic = self.state.topics[from].clone();
+        self.commit_change(TopicChange::new(vec![
+            TopicEdit::Remove {
+                index: from,
+                topic: topic.clone(),
+            },
+            TopicEdit::Insert { index: to, topic },
+        ]));
+        CommandResult::Success
+    }
+
+    pub fn swap_topics(&mut self, args: &[String]) -> CommandResult {
+        let [first, second] = args else {
+            return CommandResult::Fail("Expected two arguments: indices".to_string());
+        };
+        let (Some(first), Some(second)) = (self.parse_index(first), self.parse_index(second))
+        else {
+            return CommandResult::Fail(format!("Wrong arguments: {first} {second}"));
+        };
+        if first == second {
+            return CommandResult::Success;
         }
-        let mut to_remove: Vec<bool> = vec![false; self.state.len()];
-        let mut new_topics: Vec<String> = Vec::new();
-        for i in indices {
-            to_remove[i] = true;
+        let (first_topic, second_topic) = (&self.state.topics[first], &self.state.topics[second]);
+        self.commit_change(TopicChange::new(vec![
+            TopicEdit::Replace {
+                index: first,
+                old: first_topic.clone(),
+                new: second_topic.clone(),
+            },
+            TopicEdit::Replace {
+                index: second,
+                old: second_topic.clone(),
+                new: first_topic.clone(),
+            },
+        ]));
+        CommandResult::Success
+    }
+
+    pub fn sort_topics(&mut self, args: &[String]) -> CommandResult {
+        if args.len() > 1 {
+            return CommandResult::Fail("Incorrect number of arguments".to_string());
         }
-        for (i, topic) in self.state.iter().enumerate() {
-            if !to_remove[i] {
-                new_topics.push(topic.clone());
+        let mut sorted = self.state.topics.clone();
+        match args.first().map(String::as_str) {
+            None | Some("alpha") => sorted.sort_by_key(|topic| topic.text().to_lowercase()),
+            Some("length") => sorted.sort_by_key(|topic| topic.text().chars().count()),
+            Some("reverse") => {
+                sorted.sort_by_key(|topic| std::cmp::Reverse(topic.text().to_lowercase()))
             }
+            Some(order) => return CommandResult::Fail(format!("Unknown sort order: {order}")),
+        }
+        let edits: Vec<TopicEdit> = self
+            .state
+            .topics
+            .iter()
+            .zip(sorted)
+            .enumerate()
+            .filter(|(_index, (old, new))| old != &new)
+            .map(|(index, (old, new))| TopicEdit::Replace {
+                index,
+                old: old.clone(),
+                new,
+            })
+            .collect();
+        if !edits.is_empty() {
+            self.commit_change(TopicChange::new(edits));
         }
-        self.state.clone_from(&new_topics);
-        self.topic_history.add_new_node(new_topics);
-        self.is_modified = true;
         CommandResult::Success
     }
 
     pub fn add_topics(&mut self, args: &[String]) -> CommandResult {
-        if args.is_empty() {
+        let mut weight: u32 = 1;
+        let mut texts: Vec<&String> = Vec::new();
+        let mut args_iter = args.iter();
+        while let Some(arg) = args_iter.next() {
+            if arg == "--" {
+                texts.extend(args_iter.by_ref());
+                break;
+            }
+            if arg != "--weight" {
+                texts.push(arg);
+                continue;
+            }
+            match args_iter.next().map(|value| value.parse::<u32>()) {
+                Some(Ok(value)) if value > 0 => weight = value,
+                Some(_) => {
+                    return CommandResult::Fail("Weight must be a positive integer".to_string())
+                }
+                None => return CommandResult::Fail("Missing argument: weight".to_string()),
+            }
+        }
+        if texts.is_empty() {
             return CommandResult::Fail("Missing arguments: topics".to_string());
         }
-        for topic in args {
-            self.state.push(topic.clone());
+        if !self.allow_duplicates {
+            let mut known: HashSet<String> = self
+                .state
+                .topics
+                .iter()
+                .map(|topic| topic::normalize_text(topic.text()))
+                .collect();
+            let duplicates: Vec<&str> = texts
+                .iter()
+                .filter(|text| !known.insert(topic::normalize_text(text)))
+                .map(|text| text.as_str())
+                .collect();
+            if !duplicates.is_empty() {
+                return CommandResult::Fail(format!("Duplicate topics: {}", duplicates.join(", ")));
+            }
         }
-        self.topic_history.add_new_node(self.state.clone());
-        self.is_modified = true;
+        let mut edits: Vec<TopicEdit> = Vec::with_capacity(texts.len());
+        for (index, (id, text)) in (topic::next_id(&self.state)..).zip(texts).enumerate() {
+            let mut topic = Topic::new(id, text);
+            topic.set_weight(weight);
+            edits.push(TopicEdit::Insert {
+                index: self.state.topics.len() + index,
+                topic,
+            });
+        }
+        self.commit_change(TopicChange::new(edits));
         CommandResult::Success
     }
 
-    pub fn pick_random(&mut self) -> CommandResult {
-        if self.state.is_empty() {
+    pub fn dedupe_topics(&mut self, args: &[String]) -> CommandResult {
+        let exact = match args {
+            [] => false,
+            [mode] if mode == "normalized" => false,
+            [mode] if mode == "exact" => true,
+            [mode] => return CommandResult::Fail(format!("Unknown comparison: {mode}")),
+            _ => return CommandResult::Fail("Incorrect number of arguments".to_string()),
+        };
+        let mut known: HashSet<String> = HashSet::new();
+        let duplicates: Vec<usize> = self
+            .state
+            .topics
+            .iter()
+            .enumerate()
+            .filter(|(_index, topic)| {
+                let key = if exact {
+                    topic.text().to_string()
+                } else {
+                    topic::normalize_text(topic.text())
+                };
+                !known.insert(key)
+            })
+            .map(|(index, _topic)| index)
+            .collect();
+        if !duplicates.is_empty() {
+            self.commit_change(self.removal_change(duplicates));
+        }
+        CommandResult::Success
+    }
+
+    pub fn merge_topics(&mut self, topics: &[Topic], dedupe: bool) -> CommandResult {
+        let dedupe = dedupe || !self.allow_duplicates;
+        let mut known: HashSet<String> = self
+            .state
+            .topics
+            .iter()
+            .map(|topic| topic::normalize_text(topic.text()))
+            .collect();
+        let merged: Vec<&Topic> = topics
+            .iter()
+            .filter(|topic| !dedupe || known.insert(topic::normalize_text(topic.text())))
+            .collect();
+        if merged.is_empty() {
+            return CommandResult::Success;
+        }
+        let edits: Vec<TopicEdit> = (topic::next_id(&self.state)..)
+            .zip(merged)
+            .enumerate()
+            .map(|(offset, (id, topic))| {
+                let mut topic = topic.clone();
+                topic.set_id(id);
+                TopicEdit::Insert {
+                    index: self.state.topics.len() + offset,
+                    topic,
+                }
+            })
+            .collect();
+        self.commit_change(TopicChange::new(edits));
+        CommandResult::Success
+    }
+
+    pub fn pick_random(&mut self, count: usize) -> CommandResult {
+        if count == 0 {
+            return CommandResult::Fail("Number of topics must be positive".to_string());
+        }
+        if self.state.topics.len() < count {
             return CommandResult::Fail("Not enough topics".to_string());
         }
-        let index: usize = self.rng.gen_range(1..=self.state.len());
-        self.chosen_topic = Some((self.state[index - 1].clone(), index - 1));
+        let recent_ids = self.pick_history.recent_ids(self.no_repeat);
+        let mut chosen_indices: Vec<usize> = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut weights = self.candidate_weights(&chosen_indices, &recent_ids);
+            if weights.iter().all(|weight| *weight == 0) {
+                weights = self.candidate_weights(&chosen_indices, &[]);
+            }
+            let Ok(distribution) = WeightedIndex::new(weights) else {
+                return CommandResult::Fail("Topic weights are invalid".to_string());
+            };
+            chosen_indices.push(distribution.sample(&mut self.rng));
+        }
+
+        self.chosen_topics = chosen_indices
+            .into_iter()
+            .map(|index| (self.state.topics[index].clone(), index))
+            .collect();
+        for (topic, _index) in &self.chosen_topics {
+            self.pick_history.push(topic.clone());
+        }
         CommandResult::Success
     }
 
-    pub fn remove_chosen_topic(&mut self) -> CommandResult {
-        if let Some((_topic, index)) = &self.chosen_topic {
-            if *index >= self.state.len() {
-                return CommandResult::Fail(format!("Wrong index: {}", index));
+    pub fn remove_chosen_topics(&mut self) -> CommandResult {
+        if self.chosen_topics.is_empty() {
+            return CommandResult::Fail("No topic has been chosen".to_string());
+        }
+        let indices: Vec<usize> = self
+            .chosen_topics
+            .iter()
+            .map(|(_topic, index)| *index)
+            .collect();
+        if let Some(index) = indices
+            .iter()
+            .find(|index| **index >= self.state.topics.len())
+        {
+            return CommandResult::Fail(format!("Wrong index: {}", index));
+        }
+        self.pick_history.mark_last_removed(indices.len());
+        self.commit_change(self.removal_change(indices));
+        self.chosen_topics.clear();
+        CommandResult::Success
+    }
+
+    pub fn restore_topics(&mut self, args: &[String]) -> CommandResult {
+        if args.is_empty() {
+            return CommandResult::Fail("Missing arguments: archive indices".to_string());
+        }
+        let mut indices: Vec<usize> = Vec::with_capacity(args.len());
+        let mut wrong_args: Vec<&str> = Vec::new();
+        for arg in args {
+            match arg.parse::<usize>() {
+                Ok(index) if index > 0 && index <= self.state.archive.len() => {
+                    indices.push(index - 1)
+                }
+                _ => wrong_args.push(arg),
             }
-            self.state.remove(*index);
-            self.topic_history.add_new_node(self.state.clone());
-            self.is_modified = true;
-            CommandResult::Success
-        } else {
-            CommandResult::Fail("No topic has been chosen".to_string())
         }
+        if !wrong_args.is_empty() {
+            return CommandResult::Fail(format!(
+                "Wrong arguments: {} (archive has {} topics)",
+                wrong_args.join(", "),
+                self.state.archive.len()
+            ));
+        }
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut edits: Vec<TopicEdit> = indices
+            .iter()
+            .rev()
+            .map(|index| TopicEdit::ArchiveRemove {
+                index: *index,
+                entry: self.state.archive[*index].clone(),
+            })
+            .collect();
+        for (offset, index) in indices.into_iter().enumerate() {
+            edits.push(TopicEdit::Insert {
+                index: self.state.topics.len() + offset,
+                topic: self.state.archive[index].topic().clone(),
+            });
+        }
+        self.commit_change(TopicChange::new(edits));
+        CommandResult::Success
     }
 
     pub fn undo(&mut self) -> CommandResult {
-        if let Some(state) = self.topic_history.get_previous() {
-            self.state.clone_from(state);
-            self.topic_history.move_to_previous();
+        if let Some(change) = self.topic_history.undo() {
+            change.revert(&mut self.state);
+            self.topic_history
+                .set_state_checksum(topic::checksum(&self.state));
             self.is_modified = true;
             CommandResult::Success
         } else {
@@ -130,13 +544,268 @@ impl TopicHandler {
     }
 
     pub fn redo(&mut self) -> CommandResult {
-        if let Some(state) = self.topic_history.get_next() {
-            self.state.clone_from(state);
-            self.topic_history.move_to_next();
+        if let Some(change) = self.topic_history.redo() {
+            change.apply(&mut self.state);
+            self.topic_history
+                .set_state_checksum(topic::checksum(&self.state));
             self.is_modified = true;
             CommandResult::Success
         } else {
             CommandResult::Fail("Already at the newest change".to_string())
         }
     }
+
+    pub fn jump_to_change(&mut self, args: &[String]) -> CommandResult {
+        let [number] = args else {
+            return CommandResult::Fail("Expected one argument: change number".to_string());
+        };
+        let Ok(number) = number.parse::<usize>() else {
+            return CommandResult::Fail(format!("Wrong argument: {number}"));
+        };
+        let target = if number == 0 { None } else { Some(number) };
+        let Some(steps) = self.topic_history.jump_to(target) else {
+            return CommandResult::Fail(format!("No change with number: {number}"));
+        };
+        for step in steps {
+            match step {
+                HistoryStep::Revert(change) => change.revert(&mut self.state),
+                HistoryStep::Apply(change) => change.apply(&mut self.state),
+            }
+        }
+        self.topic_history
+            .set_state_checksum(topic::checksum(&self.state));
+        self.is_modified = true;
+        CommandResult::Success
+    }
+
+    fn parse_index(&self, str_index: &str) -> Option<usize> {
+        match str_index.parse::<usize>() {
+            Ok(index) if index > 0 && index <= self.state.topics.len() => Some(index - 1),
+            _ => None,
+        }
+    }
+
+    fn parse_index_range(&self, arg: &str) -> Option<RangeInclusive<usize>> {
+        let len = self.state.topics.len();
+        if let Some(offset) = arg.strip_prefix('-') {
+            return match offset.parse::<usize>() {
+                Ok(offset) if offset > 0 && offset <= len => Some(len - offset..=len - offset),
+                _ => None,
+            };
+        }
+        match arg.split_once('-') {
+            None => self.parse_index(arg).map(|index| index..=index),
+            Some((start, "")) => self.parse_index(start).map(|start| start..=len - 1),
+            Some((start, end)) => match (self.parse_index(start), self.parse_index(end)) {
+                (Some(start), Some(end)) if start <= end => Some(start..=end),
+                _ => None,
+            },
+        }
+    }
+
+    fn commit_change(&mut self, change: TopicChange) {
+        change.apply(&mut self.state);
+        self.topic_history.add_new_node(change);
+        self.topic_history
+            .set_state_checksum(topic::checksum(&self.state));
+        self.is_modified = true;
+    }
+
+    /// Topics are removed highest index first so that the remaining indices stay valid.
+    fn removal_change(&self, mut indices: Vec<usize>) -> TopicChange {
+        indices.sort_unstable();
+        indices.dedup();
+        let mut edits: Vec<TopicEdit> = indices
+            .iter()
+            .rev()
+            .map(|index| TopicEdit::Remove {
+                index: *index,
+                topic: self.state.topics[*index].clone(),
+            })
+            .collect();
+        for (offset, index) in indices.into_iter().enumerate() {
+            edits.push(TopicEdit::ArchiveInsert {
+                index: self.state.archive.len() + offset,
+                entry: ArchivedTopic::new(self.state.topics[index].clone()),
+            });
+        }
+        TopicChange::new(edits)
+    }
+
+    /// Weights are widened to `u64` so that their sum cannot overflow.
+    fn candidate_weights(&self, chosen_indices: &[usize], excluded_ids: &[u64]) -> Vec<u64> {
+        self.state
+            .topics
+            .iter()
+            .enumerate()
+            .map(|(index, topic)| {
+                if chosen_indices.contains(&index) || excluded_ids.contains(&topic.id()) {
+                    0
+                } else {
+                    u64::from(topic.weight())
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler(weights: &[u32], seed: u64) -> TopicHandler<StdRng> {
+        let topics = weights
+            .iter()
+            .enumerate()
+            .map(|(index, weight)| {
+                let mut topic = Topic::new(index as u64 + 1, &format!("topic {}", index + 1));
+                topic.set_weight(*weight);
+                topic
+            })
+            .collect();
+        TopicHandler::new(
+            TopicList {
+                topics,
+                archive: Vec::new(),
+            },
+            StdRng::seed_from_u64(seed),
+        )
+    }
+
+    fn pick_ids(handler: &mut TopicHandler<StdRng>, count: usize) -> Vec<u64> {
+        assert!(matches!(handler.pick_random(count), CommandResult::Success));
+        handler
+            .get_chosen_topics()
+            .iter()
+            .map(|(_index, topic)| topic.id())
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_picks_same_topics() {
+        let mut first = handler(&[1; 10], 42);
+        let mut second = handler(&[1; 10], 42);
+        for _ in 0..20 {
+            assert_eq!(pick_ids(&mut first, 3), pick_ids(&mut second, 3));
+        }
+    }
+
+    #[test]
+    fn reseed_restarts_the_sequence() {
+        let mut handler = handler(&[1; 10], 1);
+        handler.reseed(7);
+        let first = pick_ids(&mut handler, 4);
+        handler.reseed(7);
+        assert_eq!(pick_ids(&mut handler, 4), first);
+    }
+
+    #[test]
+    fn picks_are_distinct() {
+        for seed in 0..50 {
+            let mut handler = handler(&[1, 5, 1, 20, 3], seed);
+            let mut ids = pick_ids(&mut handler, 5);
+            ids.sort();
+            assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+        }
+    }
+
+    #[test]
+    fn picking_more_than_the_list_fails() {
+        let mut handler = handler(&[1, 1], 0);
+        assert!(matches!(handler.pick_random(3), CommandResult::Fail(_)));
+        assert!(matches!(handler.pick_random(0), CommandResult::Fail(_)));
+    }
+
+    #[test]
+    fn weights_are_honoured() {
+        let mut handler = handler(&[1, 99, 0], 3);
+        let mut counts = [0; 3];
+        for _ in 0..1000 {
+            counts[pick_ids(&mut handler, 1)[0] as usize - 1] += 1;
+        }
+        assert!(counts[1] > 900, "{counts:?}");
+        assert!(counts[0] > 0, "{counts:?}");
+        assert_eq!(counts[2], 0);
+    }
+
+    #[test]
+    fn large_weights_do_not_overflow() {
+        let mut handler = handler(&[u32::MAX, u32::MAX, 1], 0);
+        assert_eq!(pick_ids(&mut handler, 3).len(), 3);
+    }
+
+    #[test]
+    fn no_repeat_excludes_recent_picks() {
+        let mut handler = handler(&[1; 4], 11);
+        handler.set_no_repeat(3);
+        let mut picked: Vec<u64> = Vec::new();
+        for _ in 0..40 {
+            let id = pick_ids(&mut handler, 1)[0];
+            let window = &picked[picked.len().saturating_sub(3)..];
+            assert!(!window.contains(&id), "{id} picked again after {window:?}");
+            picked.push(id);
+        }
+    }
+
+    #[test]
+    fn no_repeat_falls_back_when_every_topic_is_recent() {
+        let mut handler = handler(&[1; 2], 5);
+        handler.set_no_repeat(10);
+        for _ in 0..10 {
+            assert_eq!(pick_ids(&mut handler, 1).len(), 1);
+        }
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn topic_ids(handler: &TopicHandler<StdRng>) -> Vec<u64> {
+        handler.get_list().topics.iter().map(Topic::id).collect()
+    }
+
+    #[test]
+    fn index_ranges_are_parsed() {
+        let handler = handler(&[1; 10], 0);
+        assert_eq!(handler.parse_index_range("4"), Some(3..=3));
+        assert_eq!(handler.parse_index_range("3-7"), Some(2..=6));
+        assert_eq!(handler.parse_index_range("10-"), Some(9..=9));
+        assert_eq!(handler.parse_index_range("8-"), Some(7..=9));
+        assert_eq!(handler.parse_index_range("-1"), Some(9..=9));
+        assert_eq!(handler.parse_index_range("-10"), Some(0..=0));
+    }
+
+    #[test]
+    fn invalid_index_ranges_are_rejected() {
+        let handler = handler(&[1; 10], 0);
+        for arg in [
+            "7-3", "0", "-0", "11", "-11", "3-11", "11-", "0-3", "a", "1-b", "",
+        ] {
+            assert_eq!(handler.parse_index_range(arg), None, "{arg:?}");
+        }
+    }
+
+    #[test]
+    fn remove_archives_every_given_topic() {
+        let mut handler = handler(&[1; 10], 0);
+        let result = handler.remove_topics(&args(&["2", "4-5", "-1", "5"]));
+        assert!(matches!(result, CommandResult::Success));
+        assert_eq!(topic_ids(&handler), vec![1, 3, 6, 7, 8, 9]);
+        let archived: Vec<u64> = handler
+            .get_archive_entries()
+            .iter()
+            .map(|(_index, entry)| entry.topic().id())
+            .collect();
+        assert_eq!(archived, vec![2, 4, 5, 10]);
+    }
+
+    #[test]
+    fn remove_with_an_invalid_argument_changes_nothing() {
+        let mut handler = handler(&[1; 10], 0);
+        let result = handler.remove_topics(&args(&["2", "7-3", "4"]));
+        assert!(matches!(result, CommandResult::Fail(_)));
+        assert_eq!(topic_ids(&handler), (1..=10).collect::<Vec<u64>>());
+        assert!(handler.get_archive_entries().is_empty());
+        assert!(!handler.is_modified(false));
+    }
 }
@@ -1,108 +1,464 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fs, path::Path};
+
+pub const DEFAULT_MAX_DEPTH: usize = 100;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoNode<T> {
+    number: usize,
+    parent: Option<usize>,
+    active_child: Option<usize>,
+    created_at: DateTime<Utc>,
+    change: T,
+}
+
+impl<T> UndoNode<T> {
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn change(&self) -> &T {
+        &self.change
+    }
+}
+
+pub enum HistoryStep<'a, T> {
+    Revert(&'a T),
+    Apply(&'a T),
+}
+
+/// Keeps reversible changes in a tree, so that changes undone before making a new
+/// one stay reachable as a separate branch. Nodes are numbered from 1 in the order
+/// they were made, the state before any change is node 0.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct UndoRedoHandler<T> {
-    list: Vec<T>,
-    current_index: usize,
-    end_index: usize,
-    at_end: bool,
+    nodes: Vec<UndoNode<T>>,
+    current: Option<usize>,
+    root_active_child: Option<usize>,
+    next_number: usize,
+    max_depth: usize,
+    state_checksum: u64,
+    #[serde(skip)]
+    is_modified: bool,
+}
+
+impl<T: Serialize + DeserializeOwned> UndoRedoHandler<T> {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::new(DEFAULT_MAX_DEPTH));
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        if !self.is_modified {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        self.is_modified = false;
+        Ok(())
+    }
 }
 
 impl<T> UndoRedoHandler<T> {
-    pub fn new() -> Self {
+    pub fn new(max_depth: usize) -> Self {
         UndoRedoHandler {
-            list: Vec::new(),
-            current_index: 0,
-            end_index: 0,
-            at_end: true,
+            nodes: Vec::new(),
+            current: None,
+            root_active_child: None,
+            next_number: 1,
+            max_depth,
+            state_checksum: 0,
+            is_modified: false,
         }
     }
 
-    pub fn add_new_node(&mut self, data: T) {
-        if !self.is_head() {
-            self.current_index += 1;
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+        self.trim();
+    }
+
+    /// Checksum of the state reached by the applied changes. A saved history is
+    /// only valid for a state with the same checksum.
+    pub fn state_checksum(&self) -> u64 {
+        self.state_checksum
+    }
+
+    pub fn set_state_checksum(&mut self, state_checksum: u64) {
+        self.state_checksum = state_checksum;
+    }
+
+    pub fn nodes(&self) -> &[UndoNode<T>] {
+        &self.nodes
+    }
+
+    pub fn branch_tips(&self) -> Vec<&UndoNode<T>> {
+        self.nodes
+            .iter()
+            .filter(|node| {
+                !self
+                    .nodes
+                    .iter()
+                    .any(|other| other.parent == Some(node.number))
+            })
+            .collect()
+    }
+
+    pub fn current_path(&self) -> Vec<usize> {
+        self.path_to_root(self.current)
+    }
+
+    pub fn add_new_node(&mut self, change: T) {
+        let number = self.next_number;
+        self.next_number += 1;
+        self.nodes.push(UndoNode {
+            number,
+            parent: self.current,
+            active_child: None,
+            created_at: Utc::now(),
+            change,
+        });
+        self.set_active_child(self.current, Some(number));
+        self.current = Some(number);
+        self.trim();
+        self.is_modified = true;
+    }
+
+    pub fn undo(&mut self) -> Option<&T> {
+        let index = self.index_of(self.current?)?;
+        self.current = self.nodes[index].parent;
+        self.is_modified = true;
+        Some(&self.nodes[index].change)
+    }
+
+    pub fn redo(&mut self) -> Option<&T> {
+        let number = self.active_child(self.current)?;
+        let index = self.index_of(number)?;
+        self.current = Some(number);
+        self.is_modified = true;
+        Some(&self.nodes[index].change)
+    }
+
+    pub fn jump_to(&mut self, target: Option<usize>) -> Option<Vec<HistoryStep<'_, T>>> {
+        if let Some(number) = target {
+            self.index_of(number)?;
+        }
+        let up = self.path_to_root(self.current);
+        let down = self.path_to_root(target);
+        let reverted: Vec<usize> = up
+            .iter()
+            .take_while(|number| !down.contains(number))
+            .copied()
+            .collect();
+        let applied: Vec<usize> = down
+            .iter()
+            .take_while(|number| !up.contains(number))
+            .copied()
+            .collect();
+
+        for number in &applied {
+            let parent = self.node(*number)?.parent;
+            self.set_active_child(parent, Some(*number));
+        }
+        self.current = target;
+        self.is_modified = true;
+
+        let mut steps = Vec::with_capacity(reverted.len() + applied.len());
+        for number in reverted {
+            steps.push(HistoryStep::Revert(&self.node(number)?.change));
         }
-        if self.current_index >= self.list.len() {
-            self.list.push(data);
-        } else {
-            self.list[self.current_index] = data;
+        for number in applied.into_iter().rev() {
+            steps.push(HistoryStep::Apply(&self.node(number)?.change));
         }
-        self.end_index = self.current_index;
-        self.at_end = false;
+        Some(steps)
     }
 
-    pub fn is_tail(&self) -> bool {
-        self.current_index == 0 && self.at_end
+    fn index_of(&self, number: usize) -> Option<usize> {
+        self.nodes
+            .binary_search_by_key(&number, |node| node.number)
+            .ok()
     }
 
-    pub fn is_head(&self) -> bool {
-        self.current_index >= self.end_index && self.at_end
+    fn node(&self, number: usize) -> Option<&UndoNode<T>> {
+        self.index_of(number).map(|index| &self.nodes[index])
     }
 
-    pub fn get_current(&self) -> Option<&T> {
-        if self.at_end {
-            return None;
+    fn active_child(&self, parent: Option<usize>) -> Option<usize> {
+        match parent {
+            Some(number) => self.node(number)?.active_child,
+            None => self.root_active_child,
         }
-        Some(&self.list[self.current_index])
     }
 
-    pub fn get_previous(&self) -> Option<&T> {
-        if self.current_index == 0 {
-            return None;
+    fn set_active_child(&mut self, parent: Option<usize>, child: Option<usize>) {
+        match parent {
+            Some(number) => {
+                if let Some(index) = self.index_of(number) {
+                    self.nodes[index].active_child = child;
+                }
+            }
+            None => self.root_active_child = child,
         }
-        Some(&self.list[self.current_index - 1])
     }
 
-    pub fn get_next(&self) -> Option<&T> {
-        if self.current_index == self.end_index {
-            return None;
+    fn path_to_root(&self, mut number: Option<usize>) -> Vec<usize> {
+        let mut path = Vec::new();
+        while let Some(current) = number {
+            path.push(current);
+            number = self.node(current).and_then(|node| node.parent);
         }
-        Some(&self.list[self.current_index + 1])
+        path
     }
 
-    pub fn move_to_previous(&mut self) -> bool {
-        if self.current_index > 0 {
-            if !self.at_end {
-                self.current_index -= 1;
+    /// Drops the oldest nodes until at most `max_depth` remain. The oldest node always
+    /// starts at the initial state; if the current state depends on it, it becomes part
+    /// of the initial state and the branches that do not contain it are dropped with it.
+    fn trim(&mut self) {
+        while self.nodes.len() > self.max_depth {
+            let oldest = self.nodes[0].number;
+            if !self.current_path().contains(&oldest) {
+                self.remove_subtree(oldest);
+                continue;
+            }
+            let other_roots: Vec<usize> = self
+                .nodes
+                .iter()
+                .filter(|node| node.parent.is_none() && node.number != oldest)
+                .map(|node| node.number)
+                .collect();
+            for number in other_roots {
+                self.remove_subtree(number);
+            }
+            self.root_active_child = self.nodes[0].active_child;
+            self.nodes.remove(0);
+            for node in &mut self.nodes {
+                if node.parent == Some(oldest) {
+                    node.parent = None;
+                }
+            }
+            if self.current == Some(oldest) {
+                self.current = None;
             }
-            self.at_end = false;
-            return true;
-        }
-        if self.at_end {
-            return false;
         }
-        self.at_end = true;
-        true
     }
 
-    pub fn move_to_next(&mut self) -> bool {
-        if self.current_index < self.end_index {
-            if !self.at_end {
-                self.current_index += 1;
+    fn remove_subtree(&mut self, number: usize) {
+        let mut removed = vec![number];
+        for node in &self.nodes {
+            if node.parent.is_some_and(|parent| removed.contains(&parent)) {
+                removed.push(node.number);
             }
-            self.at_end = false;
-            return true;
         }
-        if self.at_end {
-            return false;
+        self.nodes.retain(|node| !removed.contains(&node.number));
+        if self
+            .root_active_child
+            .is_some_and(|child| removed.contains(&child))
+        {
+            self.root_active_child = None;
+        }
+        for node in &mut self.nodes {
+            if node
+                .active_child
+                .is_some_and(|child| removed.contains(&child))
+            {
+                node.active_child = None;
+            }
         }
-        self.at_end = true;
-        true
     }
+}
 
-    pub fn remove_node(&mut self, go_to_previous: bool) -> Result<(), &'static str> {
-        if self.is_head() {
-            return Err("Invalid operation: trying to remove head.");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Changes are amounts added to an `i32` state.
+    fn apply(state: &mut i32, steps: Vec<HistoryStep<'_, i32>>) {
+        for step in steps {
+            match step {
+                HistoryStep::Revert(change) => *state -= change,
+                HistoryStep::Apply(change) => *state += change,
+            }
         }
-        if self.is_tail() {
-            return Err("Invalid operation: trying to remove tail.");
+    }
+
+    fn add(handler: &mut UndoRedoHandler<i32>, state: &mut i32, change: i32) {
+        *state += change;
+        handler.add_new_node(change);
+    }
+
+    fn undo(handler: &mut UndoRedoHandler<i32>, state: &mut i32) -> bool {
+        handler.undo().map(|change| *state -= change).is_some()
+    }
+
+    fn redo(handler: &mut UndoRedoHandler<i32>, state: &mut i32) -> bool {
+        handler.redo().map(|change| *state += change).is_some()
+    }
+
+    fn numbers(nodes: &[&UndoNode<i32>]) -> Vec<usize> {
+        nodes.iter().map(|node| node.number()).collect()
+    }
+
+    #[test]
+    fn undo_and_redo_follow_the_changes() {
+        let mut handler = UndoRedoHandler::new(DEFAULT_MAX_DEPTH);
+        let mut state = 0;
+        for change in [1, 10, 100] {
+            add(&mut handler, &mut state, change);
         }
-        self.list.remove(self.current_index);
-        self.end_index -= 1;
-        if self.current_index > self.end_index {
-            self.current_index = self.end_index;
-            self.at_end = true;
+        assert!(undo(&mut handler, &mut state));
+        assert!(undo(&mut handler, &mut state));
+        assert_eq!(state, 1);
+        assert!(redo(&mut handler, &mut state));
+        assert_eq!(state, 11);
+        assert_eq!(handler.current_path(), vec![2, 1]);
+    }
+
+    #[test]
+    fn new_change_after_undo_keeps_the_old_branch() {
+        let mut handler = UndoRedoHandler::new(DEFAULT_MAX_DEPTH);
+        let mut state = 0;
+        add(&mut handler, &mut state, 1);
+        add(&mut handler, &mut state, 10);
+        undo(&mut handler, &mut state);
+        add(&mut handler, &mut state, 100);
+        assert_eq!(state, 101);
+        assert!(!redo(&mut handler, &mut state));
+        assert_eq!(numbers(&handler.branch_tips()), vec![2, 3]);
+
+        let steps = handler.jump_to(Some(2)).unwrap();
+        assert!(matches!(
+            steps[..],
+            [HistoryStep::Revert(100), HistoryStep::Apply(10)]
+        ));
+        apply(&mut state, steps);
+        assert_eq!(state, 11);
+        assert_eq!(handler.current_path(), vec![2, 1]);
+
+        undo(&mut handler, &mut state);
+        assert!(redo(&mut handler, &mut state));
+        assert_eq!(state, 11, "redo follows the branch jumped to");
+    }
+
+    #[test]
+    fn jump_reverts_newest_first_and_applies_oldest_first() {
+        let mut handler = UndoRedoHandler::new(DEFAULT_MAX_DEPTH);
+        let mut state = 0;
+        for change in [1, 10, 100] {
+            add(&mut handler, &mut state, change);
         }
-        if go_to_previous && !self.move_to_previous() {
-            return Err("Unexpected error occurred.");
+        let steps = handler.jump_to(None).unwrap();
+        assert!(matches!(
+            steps[..],
+            [
+                HistoryStep::Revert(100),
+                HistoryStep::Revert(10),
+                HistoryStep::Revert(1)
+            ]
+        ));
+        apply(&mut state, steps);
+        assert_eq!(state, 0);
+
+        let steps = handler.jump_to(Some(3)).unwrap();
+        assert!(matches!(
+            steps[..],
+            [
+                HistoryStep::Apply(1),
+                HistoryStep::Apply(10),
+                HistoryStep::Apply(100)
+            ]
+        ));
+        apply(&mut state, steps);
+        assert_eq!(state, 111);
+        assert!(handler.jump_to(Some(9)).is_none());
+    }
+
+    #[test]
+    fn trim_folds_the_oldest_node_into_the_initial_state() {
+        let mut handler = UndoRedoHandler::new(2);
+        let mut state = 0;
+        for change in [1, 10, 100] {
+            add(&mut handler, &mut state, change);
         }
-        Ok(())
+        let nodes: Vec<&UndoNode<i32>> = handler.nodes().iter().collect();
+        assert_eq!(numbers(&nodes), vec![2, 3]);
+        assert_eq!(handler.nodes()[0].parent(), None);
+
+        while undo(&mut handler, &mut state) {}
+        assert_eq!(state, 1);
+        while redo(&mut handler, &mut state) {}
+        assert_eq!(state, 111);
+    }
+
+    #[test]
+    fn trim_to_one_drops_branches_off_the_current_path() {
+        let mut handler = UndoRedoHandler::new(1);
+        let mut state = 0;
+        add(&mut handler, &mut state, 1);
+        undo(&mut handler, &mut state);
+        add(&mut handler, &mut state, 10);
+        let nodes: Vec<&UndoNode<i32>> = handler.nodes().iter().collect();
+        assert_eq!(numbers(&nodes), vec![2]);
+        assert!(handler.jump_to(Some(1)).is_none());
+
+        add(&mut handler, &mut state, 100);
+        assert_eq!(handler.current_path(), vec![3]);
+        assert!(undo(&mut handler, &mut state));
+        assert!(!undo(&mut handler, &mut state));
+        assert_eq!(state, 10);
+        assert!(redo(&mut handler, &mut state));
+        assert_eq!(state, 110);
+    }
+
+    #[test]
+    fn trim_prunes_other_roots_when_re_rooting() {
+        let mut handler = UndoRedoHandler::new(3);
+        let mut state = 0;
+        add(&mut handler, &mut state, 1);
+        undo(&mut handler, &mut state);
+        add(&mut handler, &mut state, 10);
+        apply(&mut state, handler.jump_to(Some(1)).unwrap());
+        add(&mut handler, &mut state, 100);
+        add(&mut handler, &mut state, 1000);
+        assert_eq!(state, 1101);
+
+        let nodes: Vec<&UndoNode<i32>> = handler.nodes().iter().collect();
+        assert_eq!(numbers(&nodes), vec![3, 4]);
+        assert_eq!(numbers(&handler.branch_tips()), vec![4]);
+        apply(&mut state, handler.jump_to(None).unwrap());
+        assert_eq!(state, 1);
+        assert!(redo(&mut handler, &mut state));
+        assert_eq!(state, 101);
+    }
+
+    #[test]
+    fn zero_depth_keeps_no_history() {
+        let mut handler = UndoRedoHandler::new(DEFAULT_MAX_DEPTH);
+        let mut state = 0;
+        add(&mut handler, &mut state, 1);
+        add(&mut handler, &mut state, 10);
+        handler.set_max_depth(0);
+        assert!(handler.nodes().is_empty());
+        assert!(handler.current_path().is_empty());
+        assert!(!undo(&mut handler, &mut state));
+        assert!(!redo(&mut handler, &mut state));
+
+        add(&mut handler, &mut state, 100);
+        assert!(handler.nodes().is_empty());
+        assert_eq!(state, 111);
     }
 }
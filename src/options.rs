@@ -0,0 +1,96 @@
+use crate::{controllers::commands::StrEnum, views::output_format::OutputFormat};
+
+#[derive(Debug, Default)]
+pub struct Options {
+    list: Option<String>,
+    format: OutputFormat,
+    script: Option<String>,
+    help: bool,
+    version: bool,
+    command_args: Vec<String>,
+}
+
+impl Options {
+    pub const ALL_OPTIONS: [(&'static str, &'static str); 5] = [
+        (
+            "--list <name|index>",
+            "opens another list without changing the default",
+        ),
+        (
+            "--format plain|json|csv",
+            "output format of entries, list and pick",
+        ),
+        (
+            "--script <file>",
+            "runs the commands of a file, see help run",
+        ),
+        ("-h, --help", "shows this help"),
+        ("-V, --version", "shows the version"),
+    ];
+
+    /// Takes options off the front of the arguments, leaving the command and its
+    /// arguments in `command_args`. `--script <file>` becomes a leading `run <file>`.
+    pub fn parse(args: Vec<String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args_iter = args.into_iter();
+        while let Some(arg) = args_iter.next() {
+            match arg.as_str() {
+                "--list" => match args_iter.next() {
+                    Some(list) => options.list = Some(list),
+                    None => return Err("Missing argument: list".to_string()),
+                },
+                "--help" | "-h" => options.help = true,
+                "--version" | "-V" => options.version = true,
+                "--script" => match args_iter.next() {
+                    Some(script) => options.script = Some(script),
+                    None => return Err("Missing argument: script".to_string()),
+                },
+                "--format" => match args_iter.next() {
+                    Some(format) => {
+                        options.format = OutputFormat::from_str(&format).ok_or_else(|| {
+                            format!(
+                                "Unknown format: {format}\nAvailable formats: {}",
+                                OutputFormat::ALL_FORMATS.join(", ")
+                            )
+                        })?
+                    }
+                    None => return Err("Missing argument: format".to_string()),
+                },
+                _ => {
+                    options.command_args.push(arg);
+                    break;
+                }
+            }
+        }
+        options.command_args.extend(args_iter);
+        if let Some(script) = &options.script {
+            let mut command_args = vec!["run".to_string(), script.clone()];
+            if !options.command_args.is_empty() {
+                command_args.push(";".to_string());
+                command_args.append(&mut options.command_args);
+            }
+            options.command_args = command_args;
+        }
+        Ok(options)
+    }
+
+    pub fn list(&self) -> Option<&str> {
+        self.list.as_deref()
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    pub fn help(&self) -> bool {
+        self.help
+    }
+
+    pub fn version(&self) -> bool {
+        self.version
+    }
+
+    pub fn command_args(&self) -> &[String] {
+        &self.command_args
+    }
+}
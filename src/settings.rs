@@ -1,8 +1,9 @@
-use crate::controllers::commands::CommandResult;
+use crate::{controllers::commands::CommandResult, models::undo_redo_handler::DEFAULT_MAX_DEPTH};
 use anyhow::Result;
 use crossterm::style::Color;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     fs::{self, File},
     path::PathBuf,
 };
@@ -75,13 +76,21 @@ impl Settings {
         &self.parsed_settings.open_in
     }
 
+    pub fn undo_depth(&self) -> usize {
+        self.parsed_settings.undo_depth
+    }
+
+    pub fn aliases(&self) -> &HashMap<String, String> {
+        &self.parsed_settings.aliases
+    }
+
     pub fn lists(&self) -> &[List] {
         &self.parsed_settings.lists
     }
 
     pub fn get_list(&mut self, query: &str) -> Option<List> {
         if let Ok(index) = str::parse::<usize>(query) {
-            if index <= self.parsed_settings.lists.len() {
+            if index > 0 && index <= self.parsed_settings.lists.len() {
                 Some(self.get_list_by_index(index - 1))
             } else {
                 None
@@ -117,21 +126,32 @@ impl Settings {
             self.parsed_settings.open_in = list.name().to_string();
             CommandResult::Success
         } else {
-            CommandResult::Fail(format!("Couldn't find: \"{query}\" in lists."))
+            CommandResult::ListNotFound(format!("Couldn't find: \"{query}\" in lists."))
         }
     }
 
-    pub fn path_to_settings_file(&self) -> &PathBuf {
-        &self.path_to_settings_file
-    }
-
-    pub fn path_to_settings_dir(&self) -> &PathBuf {
-        &self.path_to_settings_dir
-    }
-
     pub fn documents_path(&self) -> &PathBuf {
         &self.documents_path
     }
+
+    /// Path to a file in `directory` holding local data kept for the given list.
+    /// Bytes other than ASCII letters and digits are percent-encoded, so that
+    /// every list name gets its own file.
+    pub fn list_data_path(&self, list_name: &str, directory: &str) -> PathBuf {
+        let file_name: String = list_name
+            .bytes()
+            .map(|byte| {
+                if byte.is_ascii_alphanumeric() {
+                    char::from(byte).to_string()
+                } else {
+                    format!("%{byte:02X}")
+                }
+            })
+            .collect();
+        self.path_to_settings_dir
+            .join(directory)
+            .join(format!("{file_name}.json"))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -139,7 +159,15 @@ impl Settings {
 struct ParsedSettings {
     open_in: String,
     open_last: bool,
+    #[serde(default = "default_undo_depth")]
+    undo_depth: usize,
     lists: Vec<List>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    aliases: HashMap<String, String>,
+}
+
+fn default_undo_depth() -> usize {
+    DEFAULT_MAX_DEPTH
 }
 
 impl ParsedSettings {
@@ -153,6 +181,7 @@ impl ParsedSettings {
         let settings = ParsedSettings {
             open_in: "New List".to_string(),
             open_last: true,
+            undo_depth: default_undo_depth(),
             lists: vec![List {
                 name: "New List".to_string(),
                 banner_path: banner_file_name.to_string(),
@@ -160,7 +189,11 @@ impl ParsedSettings {
                 list_type: ListType::Local,
                 path: "newList.txt".to_string(),
                 access_token: String::new(),
+                seed: None,
+                no_repeat: 0,
+                allow_duplicates: default_allow_duplicates(),
             }],
+            aliases: HashMap::new(),
         };
         settings
             .save_settings(path_to_settings_file)
@@ -201,6 +234,16 @@ pub struct List {
     list_type: ListType,
     path: String,
     access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(default)]
+    no_repeat: usize,
+    #[serde(default = "default_allow_duplicates")]
+    allow_duplicates: bool,
+}
+
+fn default_allow_duplicates() -> bool {
+    true
 }
 
 impl Clone for List {
@@ -212,6 +255,9 @@ impl Clone for List {
             list_type: self.list_type.clone(),
             path: self.path.clone(),
             access_token: self.access_token.clone(),
+            seed: self.seed,
+            no_repeat: self.no_repeat,
+            allow_duplicates: self.allow_duplicates,
         }
     }
 }
@@ -240,6 +286,18 @@ impl List {
     pub fn access_token(&self) -> &str {
         &self.access_token
     }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn no_repeat(&self) -> usize {
+        self.no_repeat
+    }
+
+    pub fn allow_duplicates(&self) -> bool {
+        self.allow_duplicates
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -294,6 +352,16 @@ pub enum ListType {
     GithubGist,
 }
 
+impl ListType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ListType::Local => "Local",
+            ListType::Network => "Network",
+            ListType::GithubGist => "GithubGist",
+        }
+    }
+}
+
 fn get_documents_dir() -> PathBuf {
     match directories::UserDirs::new() {
         Some(user_dirs) => user_dirs
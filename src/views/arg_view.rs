@@ -1,54 +1,257 @@
 use crate::{
+    controllers::commands::CommandHelp,
+    models::{
+        pick_history::PickRecord,
+        topic::{ArchivedTopic, Topic},
+        topic_change::TopicChange,
+        undo_redo_handler::UndoNode,
+    },
     settings::{BannerColor, List},
-    views::{parsed_command::ParsedCommand, View},
+    views::{
+        output_format::{self, OutputFormat},
+        parsed_command::ParsedCommand,
+        View,
+    },
 };
-use std::io::Write;
+use chrono::Local;
+use serde_json::json;
+use std::{
+    collections::VecDeque,
+    io::{BufRead, Write},
+};
+
+/// Separates chained commands, passed from a shell as `\;`.
+const COMMAND_SEPARATOR: &str = ";";
+
+const HISTORY_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
-pub struct ArgConsoleView<S: Write, E: Write> {
-    input: Option<Vec<String>>,
+pub struct ArgConsoleView<R: BufRead, S: Write, E: Write> {
+    input: VecDeque<Vec<String>>,
+    format: OutputFormat,
+    reader: R,
     std_writer: S,
     err_writer: E,
 }
 
-impl<S: Write, E: Write> View for ArgConsoleView<S, E> {
-    fn display_chosen_topic(&mut self, topic: &str) {
-        _ = writeln!(&mut self.std_writer, "{topic}");
+impl<R: BufRead, S: Write, E: Write> View for ArgConsoleView<R, S, E> {
+    fn display_chosen_topics(&mut self, entries: &[(usize, &Topic)], list: &List) {
+        self.print_entries(entries, list);
     }
 
     fn print_lists(&mut self, lists: &[List]) {
-        for list in lists {
-            _ = writeln!(&mut self.std_writer, "{}", list.name());
+        match self.format {
+            OutputFormat::Plain => {
+                for list in lists {
+                    _ = writeln!(&mut self.std_writer, "{}", list.name());
+                }
+            }
+            OutputFormat::Json => {
+                let lists: Vec<serde_json::Value> = lists
+                    .iter()
+                    .enumerate()
+                    .map(|(index, list)| {
+                        json!({
+                            "index": index + 1,
+                            "name": list.name(),
+                            "type": list.list_type().as_str(),
+                        })
+                    })
+                    .collect();
+                _ = writeln!(&mut self.std_writer, "{}", json!(lists));
+            }
+            OutputFormat::Csv => {
+                _ = writeln!(&mut self.std_writer, "index,name,type");
+                for (index, list) in lists.iter().enumerate() {
+                    _ = writeln!(
+                        &mut self.std_writer,
+                        "{}",
+                        output_format::csv_record(&[
+                            &(index + 1).to_string(),
+                            list.name(),
+                            list.list_type().as_str(),
+                        ])
+                    );
+                }
+            }
         }
     }
 
-    fn render(&mut self, list: &[String], _banner: &str, _color: &BannerColor) {
-        for topic in list {
-            _ = writeln!(&mut self.std_writer, "{topic}");
+    fn render(
+        &mut self,
+        entries: &[(usize, &Topic)],
+        list: &List,
+        _banner: &str,
+        _color: &BannerColor,
+    ) {
+        self.print_entries(entries, list);
+    }
+
+    fn print_pick_history(&mut self, records: &[PickRecord]) {
+        for record in records {
+            _ = writeln!(
+                &mut self.std_writer,
+                "{}\t{}{}",
+                record
+                    .picked_at()
+                    .with_timezone(&Local)
+                    .format(HISTORY_TIME_FORMAT),
+                record.topic().text(),
+                if record.removed() { "\tremoved" } else { "" }
+            );
         }
     }
 
-    fn print_error(&mut self, message: &str) {
-        _ = writeln!(&mut self.err_writer, "{message}")
+    fn print_archive(&mut self, entries: &[(usize, &ArchivedTopic)]) {
+        for (index, entry) in entries {
+            _ = writeln!(
+                &mut self.std_writer,
+                "{}\t{}\t{}",
+                index + 1,
+                entry
+                    .archived_at()
+                    .with_timezone(&Local)
+                    .format(HISTORY_TIME_FORMAT),
+                entry.topic().text()
+            );
+        }
     }
 
-    fn get_input(&mut self) -> Option<ParsedCommand> {
-        match self.input.as_mut() {
-            Some(input) => {
-                let command = ParsedCommand::parse_from_args(input.as_slice());
-                self.input = None;
-                Some(command)
+    fn print_undo_history(&mut self, nodes: &[&UndoNode<TopicChange>], current_path: &[usize]) {
+        for node in nodes {
+            _ = writeln!(
+                &mut self.std_writer,
+                "{}\t{}\t{}\t{}{}",
+                node.number(),
+                node.parent().unwrap_or(0),
+                node.created_at()
+                    .with_timezone(&Local)
+                    .format(HISTORY_TIME_FORMAT),
+                node.change().summary(),
+                if current_path.first() == Some(&node.number()) {
+                    "\tcurrent"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+
+    fn print_command_list(&mut self, commands: &[&CommandHelp]) {
+        let width = commands
+            .iter()
+            .map(|help| help.name.len())
+            .max()
+            .unwrap_or(0);
+        for help in commands {
+            _ = writeln!(
+                &mut self.std_writer,
+                "{:<width$}  {}",
+                help.name, help.description
+            );
+        }
+    }
+
+    fn print_command_help(&mut self, help: &CommandHelp) {
+        _ = writeln!(
+            &mut self.std_writer,
+            "{}\n\n{}",
+            help.syntax, help.description
+        );
+        if !help.arguments.is_empty() {
+            _ = writeln!(&mut self.std_writer, "\nArguments:");
+            for (argument, description) in help.arguments {
+                _ = writeln!(&mut self.std_writer, "  {argument:<14}{description}");
             }
-            None => None,
         }
+        _ = writeln!(&mut self.std_writer, "\nExamples:");
+        for example in help.examples {
+            _ = writeln!(&mut self.std_writer, "  {example}");
+        }
+    }
+
+    fn print_completion_script(&mut self, script: &str) {
+        _ = write!(&mut self.std_writer, "{script}");
+    }
+
+    fn print_error(&mut self, message: &str) {
+        _ = writeln!(
+            &mut self.err_writer,
+            "{}",
+            output_format::error_message(self.format, message)
+        )
+    }
+
+    fn get_input(&mut self) -> Option<ParsedCommand> {
+        self.input
+            .pop_front()
+            .map(|args| ParsedCommand::parse_from_args(&args))
+    }
+
+    fn read_lines(&mut self) -> Vec<String> {
+        self.reader.by_ref().lines().map_while(Result::ok).collect()
     }
 }
 
-impl<S: Write, E: Write> ArgConsoleView<S, E> {
-    pub fn new(args: Vec<String>, std_writer: S, err_writer: E) -> Self {
+impl<R: BufRead, S: Write, E: Write> ArgConsoleView<R, S, E> {
+    pub fn new(
+        args: Vec<String>,
+        format: OutputFormat,
+        reader: R,
+        std_writer: S,
+        err_writer: E,
+    ) -> Self {
         Self {
-            input: Some(args),
+            input: args
+                .split(|arg| arg == COMMAND_SEPARATOR)
+                .filter(|command| !command.is_empty())
+                .map(<[String]>::to_vec)
+                .collect(),
+            format,
+            reader,
             std_writer,
             err_writer,
         }
     }
+
+    /// Prints topics with their 1-based indices in the list, in the chosen format.
+    fn print_entries(&mut self, entries: &[(usize, &Topic)], list: &List) {
+        match self.format {
+            OutputFormat::Plain => {
+                for (_index, topic) in entries {
+                    _ = writeln!(&mut self.std_writer, "{}", topic.text());
+                }
+            }
+            OutputFormat::Json => {
+                let entries: Vec<serde_json::Value> = entries
+                    .iter()
+                    .map(|(index, topic)| {
+                        json!({
+                            "index": index + 1,
+                            "id": topic.id(),
+                            "text": topic.text(),
+                            "list": list.name(),
+                            "type": list.list_type().as_str(),
+                        })
+                    })
+                    .collect();
+                _ = writeln!(&mut self.std_writer, "{}", json!(entries));
+            }
+            OutputFormat::Csv => {
+                _ = writeln!(&mut self.std_writer, "index,id,text,list,type");
+                for (index, topic) in entries {
+                    _ = writeln!(
+                        &mut self.std_writer,
+                        "{}",
+                        output_format::csv_record(&[
+                            &(index + 1).to_string(),
+                            &topic.id().to_string(),
+                            topic.text(),
+                            list.name(),
+                            list.list_type().as_str(),
+                        ])
+                    );
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,302 @@
+use crate::{
+    controllers::commands::{ArgCommand, CommandHelp, StrEnum},
+    views::output_format::OutputFormat,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl StrEnum for Shell {
+    fn from_str(shell: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        match shell {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            _ => None,
+        }
+    }
+}
+
+impl Shell {
+    pub const ALL_SHELLS: [&'static str; 3] = ["bash", "zsh", "fish"];
+}
+
+/// How the arguments of a command are completed, taken from its syntax in the help table.
+struct CommandCompletion {
+    name: &'static str,
+    words: Vec<&'static str>,
+    lists_from: Option<usize>,
+    files_from: Option<usize>,
+    commands_from: Option<usize>,
+}
+
+fn command_completions() -> Vec<CommandCompletion> {
+    ArgCommand::ALL_COMMANDS
+        .iter()
+        .filter_map(|name| CommandHelp::find(name))
+        .map(|help| CommandCompletion {
+            name: help.name,
+            words: help.completion_words(),
+            lists_from: help.argument_position("<list>"),
+            files_from: help.argument_position("<file>"),
+            commands_from: help.argument_position("<command>"),
+        })
+        .collect()
+}
+
+/// Options completed before the command, without those taking a value.
+const FLAG_OPTIONS: [&str; 4] = ["-h", "--help", "-V", "--version"];
+
+/// Completion script for `name`. List names and indices are completed by running
+/// `name list` when needed, so they follow changes to the settings.
+pub fn completion_script(shell: Shell, name: &str) -> String {
+    let function: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    match shell {
+        Shell::Bash => bash_script(name, &function),
+        Shell::Zsh => zsh_script(name, &function),
+        Shell::Fish => fish_script(name, &function),
+    }
+}
+
+fn first_words() -> Vec<&'static str> {
+    let mut words: Vec<&str> = ArgCommand::ALL_COMMANDS.to_vec();
+    words.extend(["--list", "--format", "--script"]);
+    words.extend(FLAG_OPTIONS);
+    words
+}
+
+fn bash_script(name: &str, function: &str) -> String {
+    let mut arms = String::new();
+    for completion in command_completions() {
+        arms.push_str(&format!("            {})\n", completion.name));
+        if !completion.words.is_empty() {
+            arms.push_str(&format!(
+                "                words=$'{}'\n",
+                completion.words.join("\\n")
+            ));
+        }
+        if let Some(position) = completion.lists_from {
+            arms.push_str(&format!(
+                "                ((position >= {position})) && words+=$'\\n'\"$(_{function}_lists)\"\n"
+            ));
+        }
+        if let Some(position) = completion.files_from {
+            arms.push_str(&format!(
+                "                ((position >= {position})) && files=1\n"
+            ));
+        }
+        if let Some(position) = completion.commands_from {
+            arms.push_str(&format!(
+                "                ((position >= {position})) && words+=$'\\n{}'\n",
+                ArgCommand::ALL_COMMANDS.join("\\n")
+            ));
+        }
+        arms.push_str("                ;;\n");
+    }
+
+    format!(
+        r#"# bash completion for {name}, generated by `{name} completions bash`
+_{function}_lists() {{
+    local lists
+    mapfile -t lists < <("${{COMP_WORDS[0]}}" list 2>/dev/null)
+    ((${{#lists[@]}})) || return
+    printf '%s\n' "${{lists[@]}}"
+    seq 1 "${{#lists[@]}}"
+}}
+
+_{function}() {{
+    local cur=${{COMP_WORDS[COMP_CWORD]}} prev=${{COMP_WORDS[COMP_CWORD-1]}}
+    local command= position=0 words= files= i
+    local IFS=$'\n'
+    case $prev in
+        --list) words=$(_{function}_lists) ;;
+        --format) words=$'{formats}' ;;
+        --script) files=1 ;;
+    esac
+    if [[ -z $words && -z $files ]]; then
+        for ((i = 1; i < COMP_CWORD; i++)); do
+            case ${{COMP_WORDS[i]}} in
+                --list|--format|--script) [[ -z $command ]] && ((i++)) ;;
+                '\;'|';') command= position=0 ;;
+                *) if [[ -z $command ]]; then command=${{COMP_WORDS[i]}}; else ((position++)); fi ;;
+            esac
+        done
+        ((position++))
+        case $command in
+            '') words=$'{first_words}' ;;
+{arms}        esac
+    fi
+    COMPREPLY=($(compgen -W "$words" -- "$cur"))
+    [[ -n $files ]] && COMPREPLY+=($(compgen -f -- "$cur"))
+    ((${{#COMPREPLY[@]}})) && COMPREPLY=($(printf '%q\n' "${{COMPREPLY[@]}}"))
+}}
+
+complete -F _{function} {name}
+"#,
+        formats = OutputFormat::ALL_FORMATS.join("\\n"),
+        first_words = first_words().join("\\n"),
+    )
+}
+
+fn zsh_script(name: &str, function: &str) -> String {
+    let mut arms = String::new();
+    for completion in command_completions() {
+        arms.push_str(&format!("        {})\n", completion.name));
+        if !completion.words.is_empty() {
+            arms.push_str(&format!(
+                "            candidates=({})\n",
+                completion.words.join(" ")
+            ));
+        }
+        if let Some(position) = completion.lists_from {
+            arms.push_str(&format!(
+                "            ((position >= {position})) && {{ _{function}_lists; candidates+=($reply) }}\n"
+            ));
+        }
+        if let Some(position) = completion.files_from {
+            arms.push_str(&format!(
+                "            ((position >= {position})) && files=1\n"
+            ));
+        }
+        if let Some(position) = completion.commands_from {
+            arms.push_str(&format!(
+                "            ((position >= {position})) && candidates+=({})\n",
+                ArgCommand::ALL_COMMANDS.join(" ")
+            ));
+        }
+        arms.push_str("            ;;\n");
+    }
+
+    format!(
+        r#"#compdef {name}
+# zsh completion for {name}, generated by `{name} completions zsh`
+_{function}_lists() {{
+    local -a lists
+    lists=(${{(f)"$(${{words[1]}} list 2>/dev/null)"}})
+    reply=($lists)
+    (( ${{#lists}} )) && reply+=({{1..${{#lists}}}})
+}}
+
+_{function}() {{
+    local -a candidates reply
+    local command= position=0 files= i
+    case ${{words[CURRENT-1]}} in
+        --list) _{function}_lists; compadd -a reply; return ;;
+        --format) compadd {formats}; return ;;
+        --script) _files; return ;;
+    esac
+    for ((i = 2; i < CURRENT; i++)); do
+        case ${{words[i]}} in
+            --list|--format|--script) [[ -z $command ]] && ((i++)) ;;
+            '\;'|';') command= position=0 ;;
+            *) if [[ -z $command ]]; then command=${{words[i]}}; else ((position++)); fi ;;
+        esac
+    done
+    ((position++))
+    case $command in
+        '') candidates=({first_words}) ;;
+{arms}    esac
+    compadd -a candidates
+    [[ -n $files ]] && _files
+}}
+
+compdef _{function} {name}
+"#,
+        formats = OutputFormat::ALL_FORMATS.join(" "),
+        first_words = first_words().join(" "),
+    )
+}
+
+fn fish_script(name: &str, function: &str) -> String {
+    let mut lines = vec![
+        format!("complete -c {name} -f"),
+        format!(
+            "complete -c {name} -n '__{function}_using \"\"' -a '{}'",
+            ArgCommand::ALL_COMMANDS.join(" ")
+        ),
+        format!("complete -c {name} -l list -x -a '(__{function}_lists)'"),
+        format!(
+            "complete -c {name} -l format -x -a '{}'",
+            OutputFormat::ALL_FORMATS.join(" ")
+        ),
+        format!("complete -c {name} -l script -r -F"),
+        format!("complete -c {name} -s h -l help"),
+        format!("complete -c {name} -s V -l version"),
+    ];
+    for completion in command_completions() {
+        let command = completion.name;
+        if !completion.words.is_empty() {
+            lines.push(format!(
+                "complete -c {name} -n '__{function}_using {command}' -a '{}'",
+                completion.words.join(" ")
+            ));
+        }
+        if let Some(position) = completion.lists_from {
+            lines.push(format!(
+                "complete -c {name} -n '__{function}_using {command} {position}' -a '(__{function}_lists)'"
+            ));
+        }
+        if let Some(position) = completion.files_from {
+            lines.push(format!(
+                "complete -c {name} -n '__{function}_using {command} {position}' -F"
+            ));
+        }
+        if let Some(position) = completion.commands_from {
+            lines.push(format!(
+                "complete -c {name} -n '__{function}_using {command} {position}' -a '{}'",
+                ArgCommand::ALL_COMMANDS.join(" ")
+            ));
+        }
+    }
+
+    format!(
+        r#"# fish completion for {name}, generated by `{name} completions fish`
+function __{function}_lists
+    set -l lists ({name} list 2>/dev/null)
+    test (count $lists) -gt 0; or return
+    printf '%s\n' $lists
+    seq (count $lists)
+end
+
+# Succeeds when the command being completed is $argv[1], or no command for "",
+# with at least $argv[2] arguments typed when given.
+function __{function}_using
+    set -l tokens (commandline -opc)
+    set -e tokens[1]
+    set -l command ''
+    set -l position 0
+    set -l skip 0
+    for token in $tokens
+        if test $skip -eq 1
+            set skip 0
+        else if test -z "$command"; and contains -- $token --list --format --script
+            set skip 1
+        else if contains -- $token ';' '\;'
+            set command ''
+            set position 0
+        else if test -z "$command"
+            set command $token
+        else
+            set position (math $position + 1)
+        end
+    end
+    set position (math $position + 1)
+    test "$command" = "$argv[1]"; or return 1
+    test (count $argv) -lt 2; or test $position -ge $argv[2]
+end
+
+{}
+"#,
+        lines.join("\n")
+    )
+}
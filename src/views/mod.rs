@@ -1,18 +1,50 @@
 pub mod arg_view;
+pub mod completions;
+pub mod output_format;
 pub mod parsed_command;
 pub mod runtime_view;
 
+use crate::controllers::commands::CommandHelp;
+use crate::models::{
+    pick_history::PickRecord,
+    topic::{ArchivedTopic, Topic},
+    topic_change::TopicChange,
+    undo_redo_handler::UndoNode,
+};
 use crate::settings::{BannerColor, List};
 use crate::views::parsed_command::ParsedCommand;
 
 pub trait View {
-    fn display_chosen_topic(&mut self, topic: &str);
+    fn display_chosen_topics(&mut self, entries: &[(usize, &Topic)], list: &List);
 
     fn print_lists(&mut self, lists: &[List]);
 
-    fn render(&mut self, list: &[String], banner: &str, color: &BannerColor);
+    fn render(
+        &mut self,
+        entries: &[(usize, &Topic)],
+        list: &List,
+        banner: &str,
+        color: &BannerColor,
+    );
+
+    fn print_pick_history(&mut self, records: &[PickRecord]);
+
+    fn print_archive(&mut self, entries: &[(usize, &ArchivedTopic)]);
+
+    fn print_undo_history(&mut self, nodes: &[&UndoNode<TopicChange>], current_path: &[usize]);
+
+    fn print_command_list(&mut self, commands: &[&CommandHelp]);
+
+    fn print_command_help(&mut self, help: &CommandHelp);
+
+    fn print_completion_script(&mut self, script: &str);
 
     fn print_error(&mut self, message: &str);
 
     fn get_input(&mut self) -> Option<ParsedCommand>;
+
+    /// Reads the remaining lines of input, for views that take piped topics.
+    fn read_lines(&mut self) -> Vec<String> {
+        Vec::new()
+    }
 }
@@ -0,0 +1,50 @@
+use crate::controllers::commands::StrEnum;
+
+#[derive(Copy, Clone, Debug, Default)]
+pub enum OutputFormat {
+    #[default]
+    Plain,
+    Json,
+    Csv,
+}
+
+impl StrEnum for OutputFormat {
+    fn from_str(format: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        match format {
+            "plain" => Some(OutputFormat::Plain),
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+impl OutputFormat {
+    pub const ALL_FORMATS: [&'static str; 3] = ["plain", "json", "csv"];
+}
+
+/// Formats an error message for stderr, as a JSON object in JSON output.
+pub fn error_message(format: OutputFormat, message: &str) -> String {
+    match format {
+        OutputFormat::Json => serde_json::json!({ "error": message }).to_string(),
+        OutputFormat::Plain | OutputFormat::Csv => message.to_string(),
+    }
+}
+
+/// Joins the fields into one CSV record, quoting fields that need it.
+pub fn csv_record(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
@@ -1,8 +1,16 @@
 use crate::{
+    controllers::commands::CommandHelp,
+    models::{
+        pick_history::PickRecord,
+        topic::{ArchivedTopic, Topic},
+        topic_change::TopicChange,
+        undo_redo_handler::UndoNode,
+    },
     settings::{BannerColor, List},
     views::{parsed_command::ParsedCommand, View},
 };
 use arboard::Clipboard;
+use chrono::Local;
 use crossterm::{style::Stylize, terminal};
 use std::io::{self, BufRead, Write};
 
@@ -13,11 +21,23 @@ pub struct RuntimeConsoleView<R: BufRead> {
 }
 
 impl<R: BufRead> View for RuntimeConsoleView<R> {
-    fn display_chosen_topic(&mut self, topic: &str) {
-        self.copy_topic_to_clipboard(topic);
-        print!("{}", "Chosen topic: ".blue());
-        println!("{topic}");
-        print!("{}", "Remove topic [y/N]: ".green());
+    fn display_chosen_topics(&mut self, entries: &[(usize, &Topic)], _list: &List) {
+        let texts: Vec<&str> = entries.iter().map(|(_index, topic)| topic.text()).collect();
+        self.copy_topic_to_clipboard(&texts.join("\n"));
+        if let [(_index, topic)] = entries {
+            print!("{}", "Chosen topic: ".blue());
+            println!("{}", topic.text());
+            if !topic.notes().is_empty() {
+                println!("{}", topic.notes().dark_grey());
+            }
+            print!("{}", "Remove topic [y/N]: ".green());
+        } else {
+            println!("{}", "Chosen topics:".blue());
+            for text in texts {
+                println!("  {text}");
+            }
+            print!("{}", "Remove topics [y/N]: ".green());
+        }
         _ = io::stdout().flush();
     }
 
@@ -33,16 +53,24 @@ impl<R: BufRead> View for RuntimeConsoleView<R> {
         _ = io::stdout().flush();
     }
 
-    fn render(&mut self, list: &[String], banner: &str, color: &BannerColor) {
+    fn render(
+        &mut self,
+        entries: &[(usize, &Topic)],
+        _list: &List,
+        banner: &str,
+        color: &BannerColor,
+    ) {
         _ = clearscreen::clear();
         println!(
             "{}",
             crossterm::style::style(banner).with(color.as_crossterm_color())
         );
-        for (index, topic) in list.iter().enumerate() {
+        for (index, topic) in entries {
             println!(
-                "{} {topic}",
+                "{} {}{}",
                 format!("{:>2}.", (index + 1).to_string()).grey(),
+                topic.text(),
+                Self::format_metadata(topic).dark_grey()
             );
         }
         println!(
@@ -58,6 +86,95 @@ impl<R: BufRead> View for RuntimeConsoleView<R> {
         println!("\n");
     }
 
+    fn print_pick_history(&mut self, records: &[PickRecord]) {
+        if records.is_empty() {
+            println!("{}", "No topics have been picked yet.".dark_grey());
+        }
+        for record in records {
+            println!(
+                "{} {}{}",
+                record
+                    .picked_at()
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+                    .dark_grey(),
+                record.topic().text(),
+                if record.removed() { " (removed)" } else { "" }.dark_grey()
+            );
+        }
+    }
+
+    fn print_archive(&mut self, entries: &[(usize, &ArchivedTopic)]) {
+        if entries.is_empty() {
+            println!("{}", "The archive is empty.".dark_grey());
+        }
+        for (index, entry) in entries {
+            println!(
+                "{} {} {}",
+                format!("{:>2}.", (index + 1).to_string()).grey(),
+                entry
+                    .archived_at()
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+                    .dark_grey(),
+                entry.topic().text()
+            );
+        }
+    }
+
+    fn print_undo_history(&mut self, nodes: &[&UndoNode<TopicChange>], current_path: &[usize]) {
+        if nodes.is_empty() {
+            println!("{}", "No changes have been made yet.".dark_grey());
+        }
+        for node in nodes {
+            let line = format!(
+                "{:>3} <- {:<3} {} {}",
+                node.number(),
+                node.parent().unwrap_or(0),
+                node.created_at().with_timezone(&Local).format("%H:%M:%S"),
+                node.change().summary()
+            );
+            if current_path.first() == Some(&node.number()) {
+                println!("{} {}", line.green(), "(current)".green());
+            } else if current_path.contains(&node.number()) {
+                println!("{line}");
+            } else {
+                println!("{}", line.dark_grey());
+            }
+        }
+    }
+
+    fn print_command_list(&mut self, commands: &[&CommandHelp]) {
+        for help in commands {
+            println!(
+                "{} {}",
+                format!("{:<10}", help.name).green(),
+                help.description
+            );
+        }
+        println!(
+            "{}",
+            "help <command> shows the usage of a command.".dark_grey()
+        );
+    }
+
+    fn print_command_help(&mut self, help: &CommandHelp) {
+        println!("{}", help.syntax.green());
+        println!("{}", help.description);
+        for (argument, description) in help.arguments {
+            println!("  {} {}", format!("{argument:<14}").blue(), description);
+        }
+        for example in help.examples {
+            println!("  {} {}", "e.g.".dark_grey(), example);
+        }
+    }
+
+    fn print_completion_script(&mut self, script: &str) {
+        print!("{script}");
+    }
+
     fn print_error(&mut self, message: &str) {
         eprintln!("{}", message.red())
     }
@@ -78,14 +195,22 @@ impl<R: BufRead> RuntimeConsoleView<R> {
     pub fn new(reader: R) -> Self {
         Self {
             all_commands: crate::controllers::commands::RuntimeCommand::ALL_COMMANDS.join(", "),
-            clipboard: match Clipboard::new() {
-                Ok(clipboard) => Some(clipboard),
-                Err(_) => None,
-            },
+            clipboard: Clipboard::new().ok(),
             reader,
         }
     }
 
+    fn format_metadata(topic: &Topic) -> String {
+        let mut metadata = format!(" x{}", topic.weight());
+        if topic.priority() > 0 {
+            metadata.push_str(&format!(" !{}", topic.priority()));
+        }
+        for tag in topic.tags() {
+            metadata.push_str(&format!(" #{tag}"));
+        }
+        metadata
+    }
+
     fn copy_topic_to_clipboard(&mut self, topic: &str) {
         if let Some(clipboard) = &mut self.clipboard {
             _ = clipboard.set_text(topic);